@@ -0,0 +1,194 @@
+use yew::prelude::*;
+
+use crate::charts::title::ChartTitles;
+
+/// Keeps content available to screen readers while hiding it visually.
+pub(crate) const VISUALLY_HIDDEN: &str =
+    "position: absolute; width: 1px; height: 1px; padding: 0; \
+    margin: -1px; overflow: hidden; clip: rect(0, 0, 0, 0); white-space: nowrap; border: 0;";
+
+/// The most categories listed in a data table. Screen readers can't take in more than this
+/// anyway, and long streamed or decimated series would otherwise add a row per point to the
+/// page.
+const MAX_TABLE_ROWS: usize = 500;
+
+/// The data behind a chart in a form screen readers can consume: one row per category and
+/// one column per series.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct ChartDescription {
+    pub kind: &'static str,
+    pub categories: Vec<String>,
+    pub series: Vec<(String, Vec<f64>)>,
+    pub titles: ChartTitles,
+}
+
+impl ChartDescription {
+    pub fn new(kind: &'static str, categories: Vec<String>) -> Self {
+        Self {
+            kind,
+            categories,
+            series: Vec::new(),
+            titles: ChartTitles::default(),
+        }
+    }
+
+    pub fn with_series(mut self, name: &str, values: Vec<f64>) -> Self {
+        self.series.push((name.to_string(), values));
+        self
+    }
+
+    pub fn with_titles(mut self, titles: &ChartTitles) -> Self {
+        self.titles = titles.clone();
+        self
+    }
+
+    /// The chart's title and subtitle, a one-sentence summary of the chart and its caption.
+    pub fn aria_label(&self) -> String {
+        let sentence = |text: &str| match text.ends_with(['.', '!', '?']) {
+            true => text.to_string(),
+            false => format!("{}.", text),
+        };
+
+        [&self.titles.title.text, &self.titles.subtitle.text]
+            .into_iter()
+            .filter(|text| !text.is_empty())
+            .map(|text| sentence(text))
+            .chain([self.summary()])
+            .chain(
+                Some(&self.titles.caption.text)
+                    .filter(|text| !text.is_empty())
+                    .map(|text| sentence(text)),
+            )
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// The chart's type, how much data it shows and the range of the values.
+    fn summary(&self) -> String {
+        let values = self.series.iter().flat_map(|(_, values)| values.iter());
+        let min = values.clone().copied().reduce(f64::min);
+        let max = values.copied().reduce(f64::max);
+
+        let (Some(min), Some(max)) = (min, max) else {
+            return format!("{} with no data.", self.kind);
+        };
+
+        let value_count = self.categories.len();
+        format!(
+            "{} with {} series of {} {}, ranging from {} to {}.",
+            self.kind,
+            self.series.len(),
+            value_count,
+            if value_count == 1 { "value" } else { "values" },
+            min,
+            max
+        )
+    }
+
+    /// How many categories the table lists, from the first, and how many it leaves out.
+    fn table_rows(&self) -> (usize, usize) {
+        let listed = self.categories.len().min(MAX_TABLE_ROWS);
+        (listed, self.categories.len() - listed)
+    }
+
+    /// A visually hidden table with the chart's data, for screen readers. Only the first
+    /// categories are listed when there are many.
+    pub fn table(&self) -> Html {
+        let (listed, left_out) = self.table_rows();
+        let left_out = (left_out > 0).then(|| {
+            html! {
+                <tr>
+                    <td colspan={(self.series.len() + 1).to_string()}>
+                        { format!("and {} more rows", left_out) }
+                    </td>
+                </tr>
+            }
+        });
+
+        html! {
+            <table style={VISUALLY_HIDDEN}>
+                <caption>{ self.aria_label() }</caption>
+                <thead>
+                    <tr>
+                        <th scope="col">{ "Category" }</th>
+                        { for self.series.iter().map(|(name, _)| html! {
+                            <th scope="col">{ name }</th>
+                        })}
+                    </tr>
+                </thead>
+                <tbody>
+                    { for self.categories.iter().take(listed).enumerate().map(|(i, category)| html! {
+                        <tr>
+                            <th scope="row">{ category }</th>
+                            { for self.series.iter().map(|(_, values)| html! {
+                                <td>{ values.get(i).map(|value| value.to_string()).unwrap_or_default() }</td>
+                            })}
+                        </tr>
+                    })}
+                    { for left_out }
+                </tbody>
+            </table>
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summarises_type_series_and_range() {
+        let description = ChartDescription::new(
+            "Line chart",
+            vec!["Mon".to_string(), "Tue".to_string(), "Wed".to_string()],
+        )
+        .with_series("Visits", vec![10.0, 25.0, 15.0])
+        .with_series("Signups", vec![2.0, 4.0, 3.0]);
+
+        assert_eq!(
+            description.aria_label(),
+            "Line chart with 2 series of 3 values, ranging from 2 to 25."
+        );
+    }
+
+    #[test]
+    fn lists_only_the_first_categories_of_long_series() {
+        let categories = (0..10_000).map(|i| i.to_string()).collect::<Vec<_>>();
+        let description = ChartDescription::new("Line chart", categories)
+            .with_series("Value", (0..10_000).map(f64::from).collect());
+
+        assert_eq!(
+            description.table_rows(),
+            (MAX_TABLE_ROWS, 10_000 - MAX_TABLE_ROWS)
+        );
+        // The summary still covers all of them
+        assert_eq!(
+            description.aria_label(),
+            "Line chart with 1 series of 10000 values, ranging from 0 to 9999."
+        );
+    }
+
+    #[test]
+    fn summarises_empty_charts() {
+        let description =
+            ChartDescription::new("Bar chart", Vec::new()).with_series("Value", vec![]);
+
+        assert_eq!(description.aria_label(), "Bar chart with no data.");
+    }
+
+    #[test]
+    fn reads_the_titles_around_the_summary() {
+        let titles = ChartTitles::new("Revenue")
+            .with_subtitle("In thousands of dollars")
+            .with_caption("Source: finance team.");
+        let description = ChartDescription::new("Pie chart", vec!["A".to_string()])
+            .with_series("Value", vec![10.0])
+            .with_titles(&titles);
+
+        assert_eq!(
+            description.aria_label(),
+            "Revenue. In thousands of dollars. Pie chart with 1 series of 1 value, ranging from \
+             10 to 10. Source: finance team."
+        );
+    }
+}
@@ -0,0 +1,295 @@
+use gloo::render::{request_animation_frame, AnimationFrame as FrameRequest};
+use std::cell::RefCell;
+use std::rc::{Rc, Weak};
+use web_sys::window;
+
+/// Easing functions available for chart animations.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Easing {
+    Linear,
+    EaseInQuad,
+    EaseOutQuad,
+    EaseInOutQuad,
+    EaseInCubic,
+    #[default]
+    EaseOutCubic,
+    EaseInOutCubic,
+}
+
+impl Easing {
+    /// Maps linear progress `t` in `0.0..=1.0` onto the eased progress.
+    pub fn apply(&self, t: f64) -> f64 {
+        let t = t.clamp(0.0, 1.0);
+
+        match self {
+            Easing::Linear => t,
+            Easing::EaseInQuad => t * t,
+            Easing::EaseOutQuad => t * (2.0 - t),
+            Easing::EaseInOutQuad => {
+                if t < 0.5 {
+                    2.0 * t * t
+                } else {
+                    -1.0 + (4.0 - 2.0 * t) * t
+                }
+            }
+            Easing::EaseInCubic => t * t * t,
+            Easing::EaseOutCubic => 1.0 - (1.0 - t).powi(3),
+            Easing::EaseInOutCubic => {
+                if t < 0.5 {
+                    4.0 * t * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+                }
+            }
+        }
+    }
+}
+
+/// Animation options shared by every chart config.
+///
+/// Animations are skipped entirely while the user has `prefers-reduced-motion` set.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AnimationConfig {
+    pub enabled: bool,
+    pub duration_ms: u32,
+    pub easing: Easing,
+}
+
+impl AnimationConfig {
+    pub fn new(duration_ms: u32, easing: Easing) -> Self {
+        Self {
+            enabled: true,
+            duration_ms,
+            easing,
+        }
+    }
+
+    pub fn disabled() -> Self {
+        Self {
+            enabled: false,
+            ..Default::default()
+        }
+    }
+
+    fn should_animate(&self) -> bool {
+        self.enabled && self.duration_ms > 0 && !prefers_reduced_motion()
+    }
+}
+
+impl Default for AnimationConfig {
+    fn default() -> Self {
+        Self::new(600, Easing::default())
+    }
+}
+
+/// The state of a chart animation at one point in time, handed to the drawing functions.
+///
+/// Values are stored per series, so charts with a single series only use series `0`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AnimationFrame {
+    progress: f64,
+    from: Option<Vec<Vec<f64>>>,
+}
+
+impl AnimationFrame {
+    /// The first frame of an animation starting from the values in `from`, or an entry
+    /// animation if the chart hasn't been drawn before.
+    pub fn start(from: Option<Vec<Vec<f64>>>) -> Self {
+        Self {
+            progress: 0.0,
+            from,
+        }
+    }
+
+    /// A finished animation, drawing the data as-is.
+    pub fn complete() -> Self {
+        Self {
+            progress: 1.0,
+            from: None,
+        }
+    }
+
+    /// Eased progress, from `0.0` at the start of the animation to `1.0` at the end.
+    pub fn progress(&self) -> f64 {
+        self.progress
+    }
+
+    /// Whether the chart is appearing for the first time rather than updating.
+    pub fn is_entering(&self) -> bool {
+        self.from.is_none()
+    }
+
+    /// Interpolates from the previously drawn value to `target`. Values that weren't drawn
+    /// before start at `initial`.
+    pub fn interpolate(&self, series: usize, index: usize, initial: f64, target: f64) -> f64 {
+        let from = self
+            .from
+            .as_ref()
+            .and_then(|from| from.get(series))
+            .and_then(|values| values.get(index))
+            .copied()
+            .unwrap_or(initial);
+
+        from + (target - from) * self.progress
+    }
+
+    /// Interpolates a whole set of values, see [`AnimationFrame::interpolate`].
+    pub fn interpolate_all(&self, targets: &[Vec<f64>], initial: Option<f64>) -> Vec<Vec<f64>> {
+        targets
+            .iter()
+            .enumerate()
+            .map(|(series, values)| {
+                values
+                    .iter()
+                    .enumerate()
+                    .map(|(index, &target)| {
+                        self.interpolate(series, index, initial.unwrap_or(target), target)
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    pub(crate) fn set_progress(&mut self, progress: f64) {
+        self.progress = progress;
+    }
+}
+
+/// A running animation. Dropping it stops the animation where it is.
+pub(crate) struct Animation {
+    request: Rc<RefCell<Option<FrameRequest>>>,
+}
+
+impl Drop for Animation {
+    fn drop(&mut self) {
+        self.request.borrow_mut().take();
+    }
+}
+
+/// Advances `frame` from `0.0` to `1.0` over the configured duration, calling `redraw` once per
+/// animation frame.
+///
+/// When animations are disabled, or the user prefers reduced motion, `frame` jumps straight to
+/// the end and `redraw` is called once.
+pub(crate) fn animate(
+    config: &AnimationConfig,
+    frame: Rc<RefCell<AnimationFrame>>,
+    redraw: Rc<dyn Fn()>,
+) -> Animation {
+    let request = Rc::new(RefCell::new(None));
+
+    if config.should_animate() {
+        frame.borrow_mut().set_progress(0.0);
+        redraw();
+        schedule_tick(Rc::downgrade(&request), None, *config, frame, redraw);
+    } else {
+        frame.borrow_mut().set_progress(1.0);
+        redraw();
+    }
+
+    Animation { request }
+}
+
+fn schedule_tick(
+    request: Weak<RefCell<Option<FrameRequest>>>,
+    start: Option<f64>,
+    config: AnimationConfig,
+    frame: Rc<RefCell<AnimationFrame>>,
+    redraw: Rc<dyn Fn()>,
+) {
+    let Some(slot) = request.upgrade() else {
+        return;
+    };
+
+    *slot.borrow_mut() = Some(request_animation_frame(move |timestamp| {
+        let start = start.unwrap_or(timestamp);
+        let t = ((timestamp - start) / config.duration_ms as f64).min(1.0);
+
+        frame.borrow_mut().set_progress(config.easing.apply(t));
+        redraw();
+
+        if t < 1.0 {
+            schedule_tick(request, Some(start), config, frame, redraw);
+        }
+    }));
+}
+
+fn prefers_reduced_motion() -> bool {
+    window()
+        .and_then(|window| {
+            window
+                .match_media("(prefers-reduced-motion: reduce)")
+                .ok()
+                .flatten()
+        })
+        .is_some_and(|query| query.matches())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EASINGS: [Easing; 7] = [
+        Easing::Linear,
+        Easing::EaseInQuad,
+        Easing::EaseOutQuad,
+        Easing::EaseInOutQuad,
+        Easing::EaseInCubic,
+        Easing::EaseOutCubic,
+        Easing::EaseInOutCubic,
+    ];
+
+    #[test]
+    fn easings_start_at_zero_and_end_at_one() {
+        for easing in EASINGS {
+            assert_eq!(easing.apply(0.0), 0.0, "{:?}", easing);
+            assert!((easing.apply(1.0) - 1.0).abs() < 1e-9, "{:?}", easing);
+            assert!((easing.apply(0.5) - 0.5).abs() < 0.5, "{:?}", easing);
+        }
+    }
+
+    #[test]
+    fn easings_clamp_out_of_range_input() {
+        for easing in EASINGS {
+            assert_eq!(easing.apply(-1.0), 0.0, "{:?}", easing);
+            assert!((easing.apply(2.0) - 1.0).abs() < 1e-9, "{:?}", easing);
+        }
+    }
+
+    #[test]
+    fn interpolates_from_previous_values() {
+        let mut frame = AnimationFrame::start(Some(vec![vec![10.0, 20.0]]));
+        frame.set_progress(0.5);
+
+        assert!(!frame.is_entering());
+        assert_eq!(frame.interpolate(0, 0, 0.0, 30.0), 20.0);
+        assert_eq!(frame.interpolate(0, 1, 0.0, 20.0), 20.0);
+        // Points that didn't exist before start at the initial value
+        assert_eq!(frame.interpolate(0, 2, 0.0, 40.0), 20.0);
+        assert_eq!(frame.interpolate(1, 0, 8.0, 8.0), 8.0);
+    }
+
+    #[test]
+    fn entry_starts_from_initial_values() {
+        let mut frame = AnimationFrame::start(None);
+        frame.set_progress(0.25);
+
+        assert!(frame.is_entering());
+        assert_eq!(
+            frame.interpolate_all(&[vec![40.0, 80.0]], Some(0.0)),
+            vec![vec![10.0, 20.0]]
+        );
+        assert_eq!(
+            frame.interpolate_all(&[vec![40.0, 80.0]], None),
+            vec![vec![40.0, 80.0]]
+        );
+    }
+
+    #[test]
+    fn complete_frame_draws_targets() {
+        let frame = AnimationFrame::complete();
+
+        assert_eq!(frame.progress(), 1.0);
+        assert_eq!(frame.interpolate(0, 0, 0.0, 42.0), 42.0);
+    }
+}
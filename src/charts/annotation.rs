@@ -0,0 +1,402 @@
+use crate::charts::brush::Categories;
+use crate::charts::scene::{Bounds, Circle, Label, Line, Rect, SceneItem, Stroke};
+use crate::charts::theme::Theme;
+use crate::charts::zoom::ViewRange;
+
+/// A reference line, band or marker drawn over the data of a cartesian chart, e.g. a target or
+/// an SLO threshold.
+///
+///```
+/// # use visualize_yew::annotation::{Annotation, Dash};
+/// let annotations = vec![
+///     Annotation::value_line(99).with_label("SLO").with_color("#dc2626").with_dash(Dash::Dashed),
+///     Annotation::category_band(3, 5).with_label("Outage").behind_data(),
+///     Annotation::marker(4, 120, "Deploy"),
+/// ];
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Annotation {
+    pub kind: AnnotationKind,
+    /// Drawn next to the line or band, or above the marker.
+    pub label: String,
+    /// Lines and markers default to the theme's axis color, and bands to its selection color.
+    /// Bands are filled with the color as given, so pick a translucent one such as
+    /// `"rgba(220, 38, 38, 0.15)"`.
+    pub color: String,
+    /// Only applies to lines.
+    pub dash: Dash,
+    pub layer: Layer,
+}
+
+/// What an [`Annotation`] marks. Categories are data point indices and values are on the
+/// y-axis.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AnnotationKind {
+    /// A horizontal line across the plot area.
+    ValueLine(i32),
+    /// A vertical line through a category.
+    CategoryLine(usize),
+    /// A horizontal band between two values.
+    ValueBand(i32, i32),
+    /// A vertical band over the categories from the first to the last, inclusive.
+    CategoryBand(usize, usize),
+    /// A dot at a value of a category.
+    Marker(usize, i32),
+}
+
+/// How a line is dashed.
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub enum Dash {
+    #[default]
+    Solid,
+    Dashed,
+    Dotted,
+    /// Lengths in pixels drawn and skipped in turn.
+    Custom(Vec<u32>),
+}
+
+impl Dash {
+    /// The lengths drawn and skipped in turn along the line.
+    pub fn segments(&self) -> Vec<f64> {
+        match self {
+            Dash::Solid => Vec::new(),
+            Dash::Dashed => vec![6.0, 4.0],
+            Dash::Dotted => vec![2.0, 3.0],
+            Dash::Custom(segments) => segments.iter().map(|&segment| segment as f64).collect(),
+        }
+    }
+}
+
+/// Whether an annotation is drawn behind or in front of the data.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Layer {
+    Behind,
+    #[default]
+    Front,
+}
+
+impl Annotation {
+    pub fn new(kind: AnnotationKind) -> Self {
+        Self {
+            kind,
+            label: String::new(),
+            color: String::new(),
+            dash: Dash::default(),
+            layer: Layer::default(),
+        }
+    }
+
+    pub fn value_line(value: i32) -> Self {
+        Self::new(AnnotationKind::ValueLine(value))
+    }
+
+    pub fn category_line(index: usize) -> Self {
+        Self::new(AnnotationKind::CategoryLine(index))
+    }
+
+    pub fn value_band(from: i32, to: i32) -> Self {
+        Self::new(AnnotationKind::ValueBand(from, to))
+    }
+
+    pub fn category_band(first: usize, last: usize) -> Self {
+        Self::new(AnnotationKind::CategoryBand(first, last))
+    }
+
+    /// A dot at `value` of the category at `index`, with `text` as a callout above it.
+    pub fn marker(index: usize, value: i32, text: &str) -> Self {
+        Self::new(AnnotationKind::Marker(index, value)).with_label(text)
+    }
+
+    pub fn with_label(mut self, label: &str) -> Self {
+        self.label = label.into();
+        self
+    }
+
+    pub fn with_color(mut self, color: &str) -> Self {
+        self.color = color.into();
+        self
+    }
+
+    pub fn with_dash(mut self, dash: Dash) -> Self {
+        self.dash = dash;
+        self
+    }
+
+    /// Draws the annotation behind the data instead of in front of it.
+    pub fn behind_data(mut self) -> Self {
+        self.layer = Layer::Behind;
+        self
+    }
+}
+
+/// Data above `value` is drawn in `color` instead of its own.
+///
+///```
+/// # use visualize_yew::annotation::Threshold;
+/// let threshold = Threshold::new(90, "#dc2626");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Threshold {
+    pub value: i32,
+    pub color: String,
+}
+
+impl Threshold {
+    pub fn new(value: i32, color: &str) -> Self {
+        Self {
+            value,
+            color: color.into(),
+        }
+    }
+}
+
+/// The annotations on `layer`, laid out over `plot` while it shows `view`. Anything outside
+/// the plot area is left out or cut off at its edges.
+pub(crate) fn annotation_items(
+    annotations: &[Annotation],
+    layer: Layer,
+    plot: &Bounds,
+    view: &ViewRange,
+    categories: Categories,
+    theme: &Theme,
+) -> Vec<SceneItem> {
+    let (left, right) = (plot.x, plot.x + plot.width);
+    let (top, bottom) = (plot.y, plot.y + plot.height);
+    let x = |category: f64| plot.x + (category - view.x.0) / (view.x.1 - view.x.0) * plot.width;
+    let y = |value: f64| bottom - (value - view.y.0) / (view.y.1 - view.y.0) * plot.height;
+    let label = |text: &str, position: (f64, f64), align: &str, baseline: &str, color: &str| {
+        SceneItem::Label(Label {
+            text: text.to_string(),
+            position,
+            font: theme.font.clone(),
+            color: color.to_string(),
+            align: align.to_string(),
+            baseline: baseline.to_string(),
+            rotation: 0.0,
+        })
+    };
+
+    let mut items = Vec::new();
+    for annotation in annotations
+        .iter()
+        .filter(|annotation| annotation.layer == layer)
+    {
+        let line_color = match annotation.color.is_empty() {
+            true => theme.axis_color.as_str(),
+            false => annotation.color.as_str(),
+        };
+        let line = |from: (f64, f64), to: (f64, f64)| {
+            SceneItem::Line(Line {
+                from,
+                to,
+                stroke: Stroke {
+                    color: line_color.to_string(),
+                    width: 1.0,
+                },
+                dash: annotation.dash.segments(),
+            })
+        };
+        let band = |bounds: Bounds| {
+            SceneItem::Rect(Rect {
+                bounds,
+                fill: match annotation.color.is_empty() {
+                    true => theme.selection_color.clone(),
+                    false => annotation.color.clone(),
+                },
+                pattern: None,
+                outline: None,
+                point: None,
+            })
+        };
+        let text = annotation.label.as_str();
+
+        match annotation.kind {
+            AnnotationKind::ValueLine(value) => {
+                let y = y(value as f64);
+                if (top..=bottom).contains(&y) {
+                    items.push(line((left, y), (right, y)));
+                    if !text.is_empty() {
+                        items.push(label(
+                            text,
+                            (right - 4.0, y - 4.0),
+                            "right",
+                            "bottom",
+                            &theme.text_color,
+                        ));
+                    }
+                }
+            }
+            AnnotationKind::CategoryLine(index) => {
+                let x = x(categories.center(index));
+                if (left..=right).contains(&x) {
+                    items.push(line((x, top), (x, bottom)));
+                    if !text.is_empty() {
+                        items.push(label(
+                            text,
+                            (x + 4.0, top + 4.0),
+                            "left",
+                            "top",
+                            &theme.text_color,
+                        ));
+                    }
+                }
+            }
+            AnnotationKind::ValueBand(from, to) => {
+                let (from, to) = (y(from as f64), y(to as f64));
+                let (band_top, band_bottom) = (from.min(to).max(top), from.max(to).min(bottom));
+                if band_bottom > band_top {
+                    items.push(band(Bounds {
+                        y: band_top,
+                        height: band_bottom - band_top,
+                        ..*plot
+                    }));
+                    if !text.is_empty() {
+                        items.push(label(
+                            text,
+                            (left + 4.0, band_top + 4.0),
+                            "left",
+                            "top",
+                            &theme.text_color,
+                        ));
+                    }
+                }
+            }
+            AnnotationKind::CategoryBand(first, last) => {
+                let (from, to) = categories.span((first, last));
+                let (band_left, band_right) = (x(from).max(left), x(to).min(right));
+                if band_right > band_left {
+                    items.push(band(Bounds {
+                        x: band_left,
+                        width: band_right - band_left,
+                        ..*plot
+                    }));
+                    if !text.is_empty() {
+                        items.push(label(
+                            text,
+                            (band_left + 4.0, top + 4.0),
+                            "left",
+                            "top",
+                            &theme.text_color,
+                        ));
+                    }
+                }
+            }
+            AnnotationKind::Marker(index, value) => {
+                let center = (x(categories.center(index)), y(value as f64));
+                if plot.contains(center.0, center.1) {
+                    items.push(SceneItem::Circle(Circle {
+                        center,
+                        radius: 4.0,
+                        fill: Some(line_color.to_string()),
+                        outline: Some(Stroke {
+                            color: theme.border_color.clone(),
+                            width: 1.0,
+                        }),
+                        point: None,
+                    }));
+                    if !text.is_empty() {
+                        items.push(label(
+                            text,
+                            (center.0, center.1 - 8.0),
+                            "center",
+                            "bottom",
+                            line_color,
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    items
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PLOT: Bounds = Bounds {
+        x: 50.0,
+        y: 50.0,
+        width: 100.0,
+        height: 100.0,
+    };
+    const VIEW: ViewRange = ViewRange {
+        x: (0.0, 4.0),
+        y: (0.0, 100.0),
+    };
+
+    fn layout(annotations: &[Annotation], layer: Layer) -> Vec<SceneItem> {
+        annotation_items(
+            annotations,
+            layer,
+            &PLOT,
+            &VIEW,
+            Categories::Points,
+            &Theme::light(),
+        )
+    }
+
+    #[test]
+    fn lines_are_dashed_and_labelled() {
+        let annotations = [Annotation::value_line(25)
+            .with_label("Target")
+            .with_color("red")
+            .with_dash(Dash::Dashed)];
+        let items = layout(&annotations, Layer::Front);
+
+        let SceneItem::Line(line) = &items[0] else {
+            panic!("expected a line");
+        };
+        assert_eq!((line.from, line.to), ((50.0, 125.0), (150.0, 125.0)));
+        assert_eq!(line.stroke.color, "red");
+        assert_eq!(line.dash, vec![6.0, 4.0]);
+        assert!(matches!(&items[1], SceneItem::Label(label) if label.text == "Target"));
+
+        // Only on their own layer
+        assert!(layout(&annotations, Layer::Behind).is_empty());
+    }
+
+    #[test]
+    fn bands_are_cut_off_at_the_plot_area() {
+        let annotations = [
+            Annotation::value_band(50, 500).behind_data(),
+            Annotation::category_band(3, 9).behind_data(),
+        ];
+        let bands = layout(&annotations, Layer::Behind)
+            .into_iter()
+            .filter_map(|item| match item {
+                SceneItem::Rect(rect) => Some(rect.bounds),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            bands,
+            vec![
+                Bounds {
+                    height: 50.0,
+                    ..PLOT
+                },
+                Bounds {
+                    x: 112.5,
+                    width: 37.5,
+                    ..PLOT
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn markers_outside_the_view_are_left_out() {
+        let annotations = [
+            Annotation::marker(2, 50, "Deploy"),
+            Annotation::marker(9, 50, "Later"),
+            Annotation::category_line(7),
+        ];
+        let items = layout(&annotations, Layer::Front);
+
+        assert_eq!(items.len(), 2);
+        assert!(matches!(&items[0], SceneItem::Circle(circle) if circle.center == (100.0, 100.0)));
+    }
+}
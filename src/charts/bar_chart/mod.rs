@@ -1 +1,2 @@
-pub mod bar_chart;
\ No newline at end of file
+#[allow(clippy::module_inception)]
+pub mod bar_chart;
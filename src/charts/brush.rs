@@ -0,0 +1,320 @@
+use std::cell::Cell;
+use std::rc::Rc;
+use web_sys::Element;
+use yew::prelude::*;
+
+use crate::charts::interaction::CanvasState;
+use crate::charts::scene::{Bounds, Rect, SceneItem};
+use crate::charts::theme::Theme;
+use crate::charts::zoom::{PlotArea, PointerHandlers, ViewRange};
+
+/// Selecting a range of a cartesian chart by dragging across it.
+///
+/// While brushing is enabled, dragging draws the selection instead of panning a zoomed chart.
+/// Hold Shift to pan.
+///
+///```
+/// # use visualize_yew::brush::BrushConfig;
+/// let brush = BrushConfig {
+///     snap: false,
+///     ..BrushConfig::new()
+/// };
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BrushConfig {
+    pub enabled: bool,
+    /// Widens the selection to whole categories as it is drawn, rather than following the
+    /// pointer.
+    pub snap: bool,
+}
+
+impl BrushConfig {
+    pub fn new() -> Self {
+        Self {
+            enabled: true,
+            ..Default::default()
+        }
+    }
+
+    pub(crate) fn canvas_style(&self) -> &'static str {
+        match self.enabled {
+            true => "touch-action: none; cursor: crosshair;",
+            false => "",
+        }
+    }
+}
+
+impl Default for BrushConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            snap: true,
+        }
+    }
+}
+
+/// A range of data points selected by brushing.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BrushSelection {
+    /// The first and last selected data point, inclusive.
+    pub indices: (usize, usize),
+    /// The smallest and largest value of the selected points, across every series.
+    pub values: (f64, f64),
+}
+
+/// How the categories of a chart sit along its x-axis.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Categories {
+    /// A point at every whole number, like the points of a line.
+    Points,
+    /// A slot from every whole number to the next, like bars.
+    Slots,
+}
+
+impl Categories {
+    /// The first and last category in the `x` range, within the `full` range of the chart.
+    pub fn indices(&self, x: (f64, f64), full: (f64, f64)) -> Option<(usize, usize)> {
+        let (start, end) = (x.0.min(x.1), x.0.max(x.1));
+        let (first, last) = match self {
+            Categories::Points => (start.round().max(full.0), end.round().min(full.1)),
+            Categories::Slots => (
+                start.floor().max(full.0),
+                (end.ceil() - 1.0).max(start.floor()).min(full.1 - 1.0),
+            ),
+        };
+
+        (first <= last).then_some((first as usize, last as usize))
+    }
+
+    /// Where the category at `index` is centred along the x-axis.
+    pub fn center(&self, index: usize) -> f64 {
+        match self {
+            Categories::Points => index as f64,
+            Categories::Slots => index as f64 + 0.5,
+        }
+    }
+
+    /// The range along the x-axis taken up by the categories from `first` to `last`.
+    pub fn span(&self, (first, last): (usize, usize)) -> (f64, f64) {
+        let (first, last) = (first.min(last) as f64, first.max(last) as f64);
+        match self {
+            Categories::Points => (first - 0.5, last + 0.5),
+            Categories::Slots => (first, last + 1.0),
+        }
+    }
+}
+
+/// The translucent rectangle over the brushed `x` range, cut off at the edges of `plot`.
+pub(crate) fn selection_rect(
+    plot: &Bounds,
+    view: &ViewRange,
+    x: (f64, f64),
+    theme: &Theme,
+) -> Option<SceneItem> {
+    let to_x = |value: f64| {
+        (plot.x + (value - view.x.0) / (view.x.1 - view.x.0) * plot.width)
+            .clamp(plot.x, plot.x + plot.width)
+    };
+    let (left, right) = (to_x(x.0.min(x.1)), to_x(x.0.max(x.1)));
+
+    (right > left).then(|| {
+        SceneItem::Rect(Rect {
+            bounds: Bounds {
+                x: left,
+                width: right - left,
+                ..*plot
+            },
+            fill: theme.selection_color.clone(),
+            pattern: None,
+            outline: None,
+            point: None,
+        })
+    })
+}
+
+/// The smallest and largest value of the data points from `first` to `last`, if there are any.
+pub(crate) type ValueRange = Rc<dyn Fn(usize, usize) -> Option<(f64, f64)>>;
+
+/// Drags shorter than this, in pixels, clear the selection instead.
+const MIN_DRAG: f64 = 3.0;
+
+#[derive(Clone, Copy)]
+enum Gesture {
+    /// Selecting from `anchor`, in data units, where the pointer went down at `start` pixels.
+    Brush {
+        pointer: i32,
+        anchor: f64,
+        start: f64,
+    },
+    /// Passed on to the zoom handlers.
+    Pan,
+}
+
+/// Builds the handlers that brush the chart, passing gestures made with Shift held on to the
+/// `zoom` handlers.
+///
+/// The selection is stored in `state` in data units and `on_brush` is called once it is
+/// complete, or with `None` when it is cleared by clicking.
+pub(crate) fn brush_handlers(
+    config: BrushConfig,
+    state: &CanvasState,
+    area: PlotArea,
+    categories: Categories,
+    values: ValueRange,
+    on_brush: Option<Callback<Option<BrushSelection>>>,
+    zoom: PointerHandlers,
+) -> PointerHandlers {
+    if !config.enabled {
+        return zoom;
+    }
+
+    let gesture = Rc::new(Cell::new(None::<Gesture>));
+    // Where `pixel` falls along the x-axis in data units, and the full range of the x-axis
+    let to_data = {
+        let state = state.clone();
+        move |pixel: (f64, f64)| {
+            let (width, height) = state.size();
+            let (plot, full) = area(width, height);
+            let view = state.view().unwrap_or(full);
+            (
+                view.to_data(&plot, pixel).0.clamp(full.x.0, full.x.1),
+                full.x,
+            )
+        }
+    };
+    let to_data = Rc::new(to_data);
+    let selected = move |anchor: f64, x: f64, full: (f64, f64)| {
+        let indices = categories.indices((anchor, x), full);
+        let range = match (config.snap, indices) {
+            (true, Some(indices)) => categories.span(indices),
+            _ => (anchor.min(x), anchor.max(x)),
+        };
+        (indices, range)
+    };
+
+    let onpointerdown = {
+        let gesture = gesture.clone();
+        let to_data = to_data.clone();
+        let pan = zoom.onpointerdown;
+        Callback::from(move |event: PointerEvent| {
+            if event.shift_key() {
+                gesture.set(Some(Gesture::Pan));
+                pan.emit(event);
+                return;
+            }
+            if let Some(canvas) = event.target_dyn_into::<Element>() {
+                let _ignored_result = canvas.set_pointer_capture(event.pointer_id());
+            }
+
+            let (anchor, _) = to_data((event.offset_x() as f64, event.offset_y() as f64));
+            gesture.set(Some(Gesture::Brush {
+                pointer: event.pointer_id(),
+                anchor,
+                start: event.offset_x() as f64,
+            }));
+        })
+    };
+
+    let onpointermove = {
+        let gesture = gesture.clone();
+        let to_data = to_data.clone();
+        let state = state.clone();
+        let pan = zoom.onpointermove;
+        Callback::from(move |event: PointerEvent| match gesture.get() {
+            Some(Gesture::Brush {
+                pointer, anchor, ..
+            }) if pointer == event.pointer_id() => {
+                let (x, full) = to_data((event.offset_x() as f64, event.offset_y() as f64));
+                state.set_brush(Some(selected(anchor, x, full).1));
+                state.redraw();
+            }
+            Some(Gesture::Brush { .. }) => {}
+            _ => pan.emit(event),
+        })
+    };
+
+    let onpointerup = {
+        let state = state.clone();
+        let pan = zoom.onpointerup;
+        Callback::from(move |event: PointerEvent| match gesture.get() {
+            Some(Gesture::Brush {
+                pointer,
+                anchor,
+                start,
+            }) if pointer == event.pointer_id() => {
+                gesture.set(None);
+                let (x, full) = to_data((event.offset_x() as f64, event.offset_y() as f64));
+                let (indices, range) = selected(anchor, x, full);
+
+                // A click clears the selection
+                let dragged = (event.offset_x() as f64 - start).abs() >= MIN_DRAG;
+                let selection = indices.filter(|_| dragged).map(|indices| BrushSelection {
+                    indices,
+                    values: values(indices.0, indices.1).unwrap_or_default(),
+                });
+                state.set_brush(selection.map(|_| range));
+                state.redraw();
+                if let Some(on_brush) = &on_brush {
+                    on_brush.emit(selection);
+                }
+            }
+            Some(Gesture::Brush { .. }) => {}
+            _ => {
+                gesture.set(None);
+                pan.emit(event);
+            }
+        })
+    };
+
+    PointerHandlers {
+        onpointerdown,
+        onpointermove,
+        onpointerup,
+        ..zoom
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snaps_to_whole_categories() {
+        let points = Categories::Points;
+        assert_eq!(points.indices((2.7, 0.8), (0.0, 9.0)), Some((1, 3)));
+        assert_eq!(points.indices((-3.0, 20.0), (0.0, 9.0)), Some((0, 9)));
+        assert_eq!(points.span((3, 1)), (0.5, 3.5));
+
+        let slots = Categories::Slots;
+        assert_eq!(slots.indices((0.2, 2.5), (0.0, 5.0)), Some((0, 2)));
+        assert_eq!(slots.indices((1.3, 1.4), (0.0, 5.0)), Some((1, 1)));
+        assert_eq!(slots.indices((4.0, 5.0), (0.0, 5.0)), Some((4, 4)));
+        assert_eq!(slots.span((0, 2)), (0.0, 3.0));
+    }
+
+    #[test]
+    fn the_selection_is_cut_off_at_the_plot_area() {
+        let plot = Bounds {
+            x: 50.0,
+            y: 50.0,
+            width: 100.0,
+            height: 100.0,
+        };
+        let view = ViewRange::new((0.0, 10.0), (0.0, 1.0));
+        let theme = Theme::light();
+
+        let Some(SceneItem::Rect(rect)) = selection_rect(&plot, &view, (8.0, 12.0), &theme) else {
+            panic!("expected a rectangle");
+        };
+        assert_eq!(
+            rect.bounds,
+            Bounds {
+                x: 130.0,
+                width: 20.0,
+                ..plot
+            }
+        );
+        assert_eq!(rect.fill, theme.selection_color);
+        assert!(selection_rect(&plot, &view, (11.0, 12.0), &theme).is_none());
+    }
+}
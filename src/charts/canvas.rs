@@ -0,0 +1,321 @@
+use std::f64::consts::PI;
+use web_sys::{js_sys::Array, wasm_bindgen::JsValue, CanvasRenderingContext2d};
+
+use crate::charts::legend::{LegendConfig, LegendEntry};
+use crate::charts::pattern::{self, Pattern};
+use crate::charts::scene::Scene;
+use crate::charts::theme::Theme;
+
+/// The drawing operations charts are painted with, modelled on the HTML canvas 2D API.
+///
+/// Charts draw through this trait rather than a `CanvasRenderingContext2d` directly, so that
+/// the same drawing code can target other backends, such as [`crate::svg::SvgCanvas`], which
+/// also work outside the browser.
+///
+/// Paths are built in the coordinate space that is current when each point is added, and
+/// angles are in radians, exactly as on a canvas.
+pub trait Canvas {
+    fn save(&self);
+    fn restore(&self);
+    fn translate(&self, x: f64, y: f64);
+    fn rotate(&self, angle: f64);
+
+    fn set_fill_style(&self, color: &str);
+    fn set_stroke_style(&self, color: &str);
+    fn set_line_width(&self, width: f64);
+    /// How opaque everything drawn from now on is, from 0 to 1.
+    fn set_global_alpha(&self, alpha: f64);
+    /// Dashes the lines stroked from now on, alternating between lengths drawn and skipped.
+    /// An empty `segments` draws solid lines again.
+    fn set_line_dash(&self, segments: &[f64]);
+    /// A CSS font shorthand, e.g. `"bold 12px Arial"`.
+    fn set_font(&self, font: &str);
+    fn set_text_align(&self, align: &str);
+    fn set_text_baseline(&self, baseline: &str);
+
+    fn clear_rect(&self, x: f64, y: f64, width: f64, height: f64);
+    fn fill_rect(&self, x: f64, y: f64, width: f64, height: f64);
+    fn stroke_rect(&self, x: f64, y: f64, width: f64, height: f64);
+    fn fill_text(&self, text: &str, x: f64, y: f64);
+    /// The width of `text` in the current font.
+    fn measure_text(&self, text: &str) -> f64;
+
+    fn begin_path(&self);
+    fn close_path(&self);
+    fn move_to(&self, x: f64, y: f64);
+    fn line_to(&self, x: f64, y: f64);
+    fn bezier_curve_to(&self, cp1x: f64, cp1y: f64, cp2x: f64, cp2y: f64, x: f64, y: f64);
+    /// A clockwise arc, connected to the current point by a straight line if there is one.
+    fn arc(&self, x: f64, y: f64, radius: f64, start_angle: f64, end_angle: f64);
+    /// Like [`Canvas::arc`], but going anticlockwise from `start_angle` to `end_angle`.
+    fn arc_anticlockwise(&self, x: f64, y: f64, radius: f64, start_angle: f64, end_angle: f64);
+    fn rect(&self, x: f64, y: f64, width: f64, height: f64);
+    fn fill(&self);
+    fn stroke(&self);
+    fn clip(&self);
+    /// Fills the current path with `pattern` drawn in `ink`, on top of whatever was filled before.
+    fn fill_pattern(&self, pattern: Pattern, ink: &str);
+}
+
+impl Canvas for CanvasRenderingContext2d {
+    fn save(&self) {
+        CanvasRenderingContext2d::save(self);
+    }
+
+    fn restore(&self) {
+        CanvasRenderingContext2d::restore(self);
+    }
+
+    fn translate(&self, x: f64, y: f64) {
+        let _ignored_result = CanvasRenderingContext2d::translate(self, x, y);
+    }
+
+    fn rotate(&self, angle: f64) {
+        let _ignored_result = CanvasRenderingContext2d::rotate(self, angle);
+    }
+
+    fn set_fill_style(&self, color: &str) {
+        CanvasRenderingContext2d::set_fill_style(self, &JsValue::from_str(color));
+    }
+
+    fn set_stroke_style(&self, color: &str) {
+        CanvasRenderingContext2d::set_stroke_style(self, &JsValue::from_str(color));
+    }
+
+    fn set_line_width(&self, width: f64) {
+        CanvasRenderingContext2d::set_line_width(self, width);
+    }
+
+    fn set_global_alpha(&self, alpha: f64) {
+        CanvasRenderingContext2d::set_global_alpha(self, alpha);
+    }
+
+    fn set_line_dash(&self, segments: &[f64]) {
+        let segments = segments
+            .iter()
+            .map(|&segment| JsValue::from_f64(segment))
+            .collect::<Array>();
+        let _ignored_result = CanvasRenderingContext2d::set_line_dash(self, &segments);
+    }
+
+    fn set_font(&self, font: &str) {
+        CanvasRenderingContext2d::set_font(self, font);
+    }
+
+    fn set_text_align(&self, align: &str) {
+        CanvasRenderingContext2d::set_text_align(self, align);
+    }
+
+    fn set_text_baseline(&self, baseline: &str) {
+        CanvasRenderingContext2d::set_text_baseline(self, baseline);
+    }
+
+    fn clear_rect(&self, x: f64, y: f64, width: f64, height: f64) {
+        CanvasRenderingContext2d::clear_rect(self, x, y, width, height);
+    }
+
+    fn fill_rect(&self, x: f64, y: f64, width: f64, height: f64) {
+        CanvasRenderingContext2d::fill_rect(self, x, y, width, height);
+    }
+
+    fn stroke_rect(&self, x: f64, y: f64, width: f64, height: f64) {
+        CanvasRenderingContext2d::stroke_rect(self, x, y, width, height);
+    }
+
+    fn fill_text(&self, text: &str, x: f64, y: f64) {
+        let _ignored_result = CanvasRenderingContext2d::fill_text(self, text, x, y);
+    }
+
+    fn measure_text(&self, text: &str) -> f64 {
+        CanvasRenderingContext2d::measure_text(self, text).map_or(0.0, |metrics| metrics.width())
+    }
+
+    fn begin_path(&self) {
+        CanvasRenderingContext2d::begin_path(self);
+    }
+
+    fn close_path(&self) {
+        CanvasRenderingContext2d::close_path(self);
+    }
+
+    fn move_to(&self, x: f64, y: f64) {
+        CanvasRenderingContext2d::move_to(self, x, y);
+    }
+
+    fn line_to(&self, x: f64, y: f64) {
+        CanvasRenderingContext2d::line_to(self, x, y);
+    }
+
+    fn bezier_curve_to(&self, cp1x: f64, cp1y: f64, cp2x: f64, cp2y: f64, x: f64, y: f64) {
+        CanvasRenderingContext2d::bezier_curve_to(self, cp1x, cp1y, cp2x, cp2y, x, y);
+    }
+
+    fn arc(&self, x: f64, y: f64, radius: f64, start_angle: f64, end_angle: f64) {
+        let _ignored_result =
+            CanvasRenderingContext2d::arc(self, x, y, radius, start_angle, end_angle);
+    }
+
+    fn arc_anticlockwise(&self, x: f64, y: f64, radius: f64, start_angle: f64, end_angle: f64) {
+        let _ignored_result =
+            self.arc_with_anticlockwise(x, y, radius, start_angle, end_angle, true);
+    }
+
+    fn rect(&self, x: f64, y: f64, width: f64, height: f64) {
+        CanvasRenderingContext2d::rect(self, x, y, width, height);
+    }
+
+    fn fill(&self) {
+        CanvasRenderingContext2d::fill(self);
+    }
+
+    fn stroke(&self) {
+        CanvasRenderingContext2d::stroke(self);
+    }
+
+    fn clip(&self) {
+        CanvasRenderingContext2d::clip(self);
+    }
+
+    fn fill_pattern(&self, pattern: Pattern, ink: &str) {
+        pattern::fill_pattern(self, pattern, ink);
+    }
+}
+
+/// A chart that can be drawn onto any [`Canvas`], implemented by every chart's props.
+///
+/// This is what [`crate::svg::to_svg`] and [`crate::export::ChartExport`] use to draw a chart
+/// outside of its component.
+pub trait Chart {
+    /// Lays the chart out, fully animated in, at `width` by `height` CSS pixels.
+    fn scene(&self, width: f64, height: f64, theme: &Theme) -> Scene;
+
+    /// Draws the chart, fully animated in, at `width` by `height` CSS pixels.
+    fn draw(&self, canvas: &dyn Canvas, width: f64, height: f64, theme: &Theme) {
+        self.scene(width, height, theme).paint(canvas);
+    }
+
+    /// The legend shown next to the chart, or nothing if the legend is turned off.
+    fn legend(&self) -> Vec<LegendEntry>;
+
+    /// Where the legend goes and how its entries are laid out.
+    fn legend_config(&self) -> LegendConfig {
+        LegendConfig::default()
+    }
+
+    /// The distance from the top, right, bottom and left edges of the chart to its plot,
+    /// which a legend inside the chart is placed within.
+    fn plot_inset(&self, _theme: &Theme) -> (f64, f64, f64, f64) {
+        (0.0, 0.0, 0.0, 0.0)
+    }
+
+    /// The theme set through the chart's `theme` prop, if any.
+    fn theme(&self) -> Option<&Theme>;
+}
+
+/// A 2D affine transform `[a c e; b d f]`, as used by the canvas API.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct Transform {
+    pub a: f64,
+    pub b: f64,
+    pub c: f64,
+    pub d: f64,
+    pub e: f64,
+    pub f: f64,
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self {
+            a: 1.0,
+            b: 0.0,
+            c: 0.0,
+            d: 1.0,
+            e: 0.0,
+            f: 0.0,
+        }
+    }
+}
+
+impl Transform {
+    pub fn apply(&self, x: f64, y: f64) -> (f64, f64) {
+        (
+            self.a * x + self.c * y + self.e,
+            self.b * x + self.d * y + self.f,
+        )
+    }
+
+    pub fn translate(&mut self, x: f64, y: f64) {
+        self.e += self.a * x + self.c * y;
+        self.f += self.b * x + self.d * y;
+    }
+
+    pub fn rotate(&mut self, angle: f64) {
+        let (sin, cos) = angle.sin_cos();
+        *self = Self {
+            a: self.a * cos + self.c * sin,
+            b: self.b * cos + self.d * sin,
+            c: self.c * cos - self.a * sin,
+            d: self.d * cos - self.b * sin,
+            ..*self
+        };
+    }
+
+    /// The rotation applied to the x-axis, in degrees.
+    pub fn rotation_degrees(&self) -> f64 {
+        self.b.atan2(self.a).to_degrees()
+    }
+}
+
+/// The angle an arc from `start_angle` to `end_angle` sweeps, negative when it goes
+/// anticlockwise. Like on a canvas, a difference of a full turn or more draws a full circle.
+pub(crate) fn arc_sweep(start_angle: f64, end_angle: f64, anticlockwise: bool) -> f64 {
+    let turn = 2.0 * PI;
+    if anticlockwise {
+        if start_angle - end_angle >= turn {
+            -turn
+        } else {
+            -(start_angle - end_angle).rem_euclid(turn)
+        }
+    } else if end_angle - start_angle >= turn {
+        turn
+    } else {
+        (end_angle - start_angle).rem_euclid(turn)
+    }
+}
+
+/// The two control points and the end point of a cubic Bézier curve.
+pub(crate) type BezierCurve = [(f64, f64); 3];
+
+/// Approximates an arc with one cubic Bézier curve per quarter turn or less, for backends
+/// without arcs of their own. Returns the start point and the control points and end point
+/// of each curve.
+pub(crate) fn arc_curves(
+    x: f64,
+    y: f64,
+    radius: f64,
+    start_angle: f64,
+    sweep: f64,
+) -> ((f64, f64), Vec<BezierCurve>) {
+    let point_at = |angle: f64| (x + radius * angle.cos(), y + radius * angle.sin());
+
+    let segments = (sweep.abs() / (PI / 2.0)).ceil().max(1.0) as usize;
+    let step = sweep / segments as f64;
+    let handle = 4.0 / 3.0 * (step / 4.0).tan() * radius;
+
+    let curves = (0..segments)
+        .map(|segment| {
+            let from = start_angle + step * segment as f64;
+            let to = from + step;
+            let (from_x, from_y) = point_at(from);
+            let (to_x, to_y) = point_at(to);
+
+            [
+                (from_x - handle * from.sin(), from_y + handle * from.cos()),
+                (to_x + handle * to.sin(), to_y - handle * to.cos()),
+                (to_x, to_y),
+            ]
+        })
+        .collect();
+
+    (point_at(start_angle), curves)
+}
@@ -0,0 +1,253 @@
+use yew::prelude::*;
+
+use crate::charts::interaction::CanvasState;
+use crate::charts::scene::{Bounds, Label, Line, SceneItem, Stroke};
+use crate::charts::theme::Theme;
+use crate::charts::zoom::{PlotArea, PointerHandlers, ViewRange};
+
+/// A vertical line that follows the pointer from category to category, with one tooltip
+/// listing the value of every series there.
+///
+///```
+/// # use visualize_yew::crosshair::CrosshairConfig;
+/// let crosshair = CrosshairConfig {
+///     horizontal_guide: true,
+///     ..CrosshairConfig::new()
+/// };
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct CrosshairConfig {
+    pub enabled: bool,
+    /// Also draws a horizontal line at the pointer, labelled with the value there.
+    pub horizontal_guide: bool,
+}
+
+impl CrosshairConfig {
+    pub fn new() -> Self {
+        Self {
+            enabled: true,
+            ..Default::default()
+        }
+    }
+}
+
+/// Where the pointer is over a chart, in data units.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Hover {
+    /// The category nearest to the pointer along the x-axis.
+    pub index: usize,
+    /// The value under the pointer along the y-axis.
+    pub value: f64,
+}
+
+impl Hover {
+    /// What the pointer at `(x, y)` is over when `plot` shows `view` of a chart whose full
+    /// range is `full`, or `None` when it is outside the plot area.
+    pub(crate) fn at(
+        plot: &Bounds,
+        view: &ViewRange,
+        full: &ViewRange,
+        (x, y): (f64, f64),
+    ) -> Option<Self> {
+        if !plot.contains(x, y) || full.x.1 < full.x.0 {
+            return None;
+        }
+
+        let (index, value) = view.to_data(plot, (x, y));
+        Some(Self {
+            index: index.round().clamp(full.x.0, full.x.1) as usize,
+            value,
+        })
+    }
+}
+
+/// Builds the handlers that track the pointer in `state` while it is over the plot area,
+/// passing every event on to `pointer` as well.
+///
+/// `hovered` holds the hovered category, so that the chart re-renders its tooltip only when
+/// the pointer moves to another one, and `on_hover` is called whenever it does.
+pub(crate) fn crosshair_handlers(
+    config: CrosshairConfig,
+    state: &CanvasState,
+    area: PlotArea,
+    hovered: &UseStateHandle<Option<usize>>,
+    on_hover: Option<Callback<Option<usize>>>,
+    pointer: PointerHandlers,
+) -> PointerHandlers {
+    if !config.enabled {
+        return pointer;
+    }
+
+    let update = {
+        let state = state.clone();
+        let hovered = hovered.clone();
+        move |hover: Option<Hover>| {
+            let previous = state.hover();
+            if hover == previous {
+                return;
+            }
+            state.set_hover(hover);
+            state.redraw();
+
+            let index = hover.map(|hover| hover.index);
+            hovered.set(index);
+            if let Some(on_hover) = &on_hover {
+                if index != previous.map(|hover| hover.index) {
+                    on_hover.emit(index);
+                }
+            }
+        }
+    };
+
+    let onpointermove = {
+        let state = state.clone();
+        let update = update.clone();
+        let next = pointer.onpointermove;
+        Callback::from(move |event: PointerEvent| {
+            let (width, height) = state.size();
+            let (plot, full) = area(width, height);
+            let view = state.view().unwrap_or(full);
+            let position = (event.offset_x() as f64, event.offset_y() as f64);
+            update(Hover::at(&plot, &view, &full, position));
+            next.emit(event);
+        })
+    };
+
+    let onpointerleave = {
+        let next = pointer.onpointerleave;
+        Callback::from(move |event: PointerEvent| {
+            update(None);
+            next.emit(event);
+        })
+    };
+
+    PointerHandlers {
+        onpointermove,
+        onpointerleave,
+        ..pointer
+    }
+}
+
+/// The vertical line through the hovered category at `x`, and the horizontal guide at the `y`
+/// of `guide` labelled with its value, if there is one.
+pub(crate) fn crosshair_lines(
+    plot: &Bounds,
+    x: f64,
+    guide: Option<(f64, f64)>,
+    theme: &Theme,
+) -> Vec<SceneItem> {
+    let stroke = Stroke {
+        color: theme.axis_color.clone(),
+        width: 1.0,
+    };
+    let mut items = Vec::new();
+
+    if (plot.x..=plot.x + plot.width).contains(&x) {
+        items.push(SceneItem::Line(Line {
+            from: (x, plot.y),
+            to: (x, plot.y + plot.height),
+            stroke: stroke.clone(),
+            dash: Vec::new(),
+        }));
+    }
+
+    if let Some((y, value)) = guide.filter(|(y, _)| (plot.y..=plot.y + plot.height).contains(y)) {
+        items.push(SceneItem::Line(Line {
+            from: (plot.x, y),
+            to: (plot.x + plot.width, y),
+            stroke,
+            dash: Vec::new(),
+        }));
+        items.push(SceneItem::Label(Label {
+            text: format!("{}", value.round()),
+            position: (plot.x + 4.0, y - 4.0),
+            font: theme.font.clone(),
+            color: theme.text_color.clone(),
+            align: "left".to_string(),
+            baseline: "bottom".to_string(),
+            rotation: 0.0,
+        }));
+    }
+
+    items
+}
+
+/// A tooltip anchored at `(x, y)` relative to the chart's container, listing one row per
+/// series under `title`. Each row is a color swatch and its text.
+pub(crate) fn shared_tooltip(
+    theme: &Theme,
+    x: f64,
+    y: f64,
+    title: &str,
+    rows: &[(String, String)],
+) -> Html {
+    let style = format!(
+        "position: absolute; left: {}px; top: {}px; transform: translate(-50%, calc(-100% - 8px)); \
+         pointer-events: none; padding: 4px 8px; border-radius: 4px; white-space: nowrap; \
+         font: {}; background: {}; color: {};",
+        x, y, theme.font, theme.tooltip_background, theme.tooltip_text_color
+    );
+
+    html! {
+        <div style={style} aria-hidden="true">
+            if !title.is_empty() {
+                <div style="font-weight: bold;">{ title }</div>
+            }
+            { for rows.iter().map(|(color, text)| html! {
+                <div style="display: flex; align-items: center; gap: 4px;">
+                    <span style={format!("width: 8px; height: 8px; display: inline-block; background: {};", color)}></span>
+                    { text }
+                </div>
+            })}
+        </div>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PLOT: Bounds = Bounds {
+        x: 50.0,
+        y: 50.0,
+        width: 100.0,
+        height: 100.0,
+    };
+
+    #[test]
+    fn hovers_the_nearest_category() {
+        let full = ViewRange::new((0.0, 4.0), (0.0, 10.0));
+
+        assert_eq!(
+            Hover::at(&PLOT, &full, &full, (85.0, 100.0)),
+            Some(Hover {
+                index: 1,
+                value: 5.0
+            })
+        );
+        assert_eq!(
+            Hover::at(&PLOT, &full, &full, (140.0, 150.0)).map(|hover| hover.index),
+            Some(4)
+        );
+        assert_eq!(Hover::at(&PLOT, &full, &full, (20.0, 100.0)), None);
+
+        // Zoomed in, the same pixels land on other categories
+        let view = ViewRange::new((2.0, 4.0), (0.0, 10.0));
+        assert_eq!(
+            Hover::at(&PLOT, &view, &full, (60.0, 100.0)).map(|hover| hover.index),
+            Some(2)
+        );
+    }
+
+    #[test]
+    fn the_guide_is_only_drawn_inside_the_plot() {
+        let theme = Theme::light();
+
+        assert_eq!(crosshair_lines(&PLOT, 100.0, None, &theme).len(), 1);
+        assert_eq!(
+            crosshair_lines(&PLOT, 100.0, Some((75.0, 42.4)), &theme).len(),
+            3
+        );
+        assert!(crosshair_lines(&PLOT, 10.0, Some((10.0, 42.4)), &theme).is_empty());
+    }
+}
@@ -0,0 +1,153 @@
+/// How a line with more points than pixels is thinned out before it is drawn.
+///
+/// Only the drawing is decimated. Keyboard navigation, tooltips and the data table still see
+/// every point.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Decimation {
+    /// Largest-Triangle-Three-Buckets, which keeps one point per pixel column and preserves the
+    /// visual shape of the line well.
+    #[default]
+    Lttb,
+    /// Keeps the smallest and largest value of every pixel column, so no peak is ever lost.
+    MinMax,
+    /// Draws every point.
+    Disabled,
+}
+
+impl Decimation {
+    /// The indices of the `points` to draw across `pixels` pixel columns, or `None` when every
+    /// point should be drawn. `points` are in canvas coordinates and sorted by `x`.
+    pub fn select(&self, points: &[(f64, f64)], pixels: usize) -> Option<Vec<usize>> {
+        match self {
+            Decimation::Lttb if points.len() > pixels => Some(lttb(points, pixels)),
+            Decimation::MinMax if points.len() > pixels * 2 => Some(min_max(points, pixels)),
+            _ => None,
+        }
+    }
+}
+
+/// Picks `threshold` of `points` with the Largest-Triangle-Three-Buckets algorithm and returns
+/// their indices in order. The first and last point are always kept.
+pub fn lttb(points: &[(f64, f64)], threshold: usize) -> Vec<usize> {
+    let len = points.len();
+    if threshold >= len || threshold < 3 {
+        return (0..len).collect();
+    }
+
+    // Everything between the first and last point is split into equally sized buckets
+    let bucket_size = (len - 2) as f64 / (threshold - 2) as f64;
+    let bucket_start = |bucket: usize| ((bucket as f64 * bucket_size) as usize + 1).min(len - 1);
+
+    let mut kept = Vec::with_capacity(threshold);
+    kept.push(0);
+    let mut previous = points[0];
+
+    for bucket in 0..threshold - 2 {
+        // The average of the next bucket stands in for the point that will be picked there
+        let next = match bucket + 3 < threshold {
+            true => &points[bucket_start(bucket + 1)..bucket_start(bucket + 2)],
+            false => &points[len - 1..],
+        };
+        let count = next.len() as f64;
+        let average = next.iter().fold((0.0, 0.0), |(x, y), point| {
+            (x + point.0 / count, y + point.1 / count)
+        });
+
+        // Keep the point that spans the largest triangle with its neighbors
+        let area = |point: (f64, f64)| {
+            ((previous.0 - average.0) * (point.1 - previous.1)
+                - (previous.0 - point.0) * (average.1 - previous.1))
+                .abs()
+        };
+        let best = (bucket_start(bucket)..bucket_start(bucket + 1))
+            .max_by(|&a, &b| area(points[a]).total_cmp(&area(points[b])))
+            .unwrap_or(bucket_start(bucket));
+
+        kept.push(best);
+        previous = points[best];
+    }
+
+    kept.push(len - 1);
+    kept
+}
+
+/// Splits `points` into `buckets` runs of neighboring points and keeps the lowest and highest
+/// of each. Returns their indices in order, always including the first and last point.
+pub fn min_max(points: &[(f64, f64)], buckets: usize) -> Vec<usize> {
+    let len = points.len();
+    if buckets == 0 || buckets * 2 >= len {
+        return (0..len).collect();
+    }
+
+    let mut kept = Vec::with_capacity(buckets * 2 + 2);
+    kept.push(0);
+    for bucket in 0..buckets {
+        let range = bucket * len / buckets..(bucket + 1) * len / buckets;
+        let by_value = |&a: &usize, &b: &usize| points[a].1.total_cmp(&points[b].1);
+        let (Some(low), Some(high)) = (
+            range.clone().min_by(by_value),
+            range.clone().max_by(by_value),
+        ) else {
+            continue;
+        };
+
+        kept.push(low.min(high));
+        kept.push(low.max(high));
+    }
+    kept.push(len - 1);
+
+    kept.dedup();
+    kept
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wave(len: usize) -> Vec<(f64, f64)> {
+        (0..len)
+            .map(|i| (i as f64, (i as f64 / 10.0).sin() * 100.0))
+            .collect()
+    }
+
+    #[test]
+    fn lttb_keeps_the_threshold_in_order() {
+        let mut points = wave(10_000);
+        points[5_000].1 = 1_000.0;
+        let kept = lttb(&points, 500);
+
+        assert_eq!(kept.len(), 500);
+        assert_eq!((kept[0], kept[499]), (0, 9_999));
+        assert!(kept.windows(2).all(|pair| pair[0] < pair[1]));
+        // A spike is the largest triangle in its bucket
+        assert!(kept.contains(&5_000));
+    }
+
+    #[test]
+    fn min_max_keeps_every_extreme() {
+        let points = wave(1_000);
+        let kept = min_max(&points, 10);
+
+        assert!(kept.len() <= 22);
+        assert_eq!((kept[0], *kept.last().unwrap()), (0, 999));
+        assert!(kept.windows(2).all(|pair| pair[0] < pair[1]));
+        for bucket in 0..10 {
+            let range = bucket * 100..(bucket + 1) * 100;
+            let highest = range
+                .clone()
+                .max_by(|&a, &b| points[a].1.total_cmp(&points[b].1))
+                .unwrap();
+            assert!(kept.contains(&highest));
+        }
+    }
+
+    #[test]
+    fn short_lines_are_drawn_as_they_are() {
+        let points = wave(100);
+
+        assert_eq!(Decimation::Lttb.select(&points, 200), None);
+        assert_eq!(Decimation::MinMax.select(&points, 60), None);
+        assert_eq!(Decimation::Disabled.select(&wave(10_000), 10), None);
+        assert_eq!(Decimation::Lttb.select(&points, 50).unwrap().len(), 50);
+    }
+}
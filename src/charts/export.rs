@@ -0,0 +1,215 @@
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+use web_sys::{
+    wasm_bindgen::{JsCast, JsValue},
+    window, CanvasRenderingContext2d, HtmlAnchorElement, HtmlCanvasElement,
+};
+use yew::prelude::*;
+
+use crate::charts::canvas::Chart;
+use crate::charts::legend::LegendLayout;
+use crate::charts::theme::Theme;
+
+/// Options for exporting a chart as an image.
+///
+///```
+/// # use visualize_yew::export::ExportOptions;
+/// let options = ExportOptions {
+///     width: Some(1200),
+///     pixel_ratio: 2.0,
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExportOptions {
+    /// Width of the chart in CSS pixels, not counting the legend. Defaults to the width the
+    /// chart is currently displayed at.
+    pub width: Option<u32>,
+    /// Height of the chart in CSS pixels, not counting the legend. Defaults to the height the
+    /// chart is currently displayed at.
+    pub height: Option<u32>,
+    /// Image pixels per CSS pixel, e.g. `2.0` for an image that stays sharp on high density
+    /// displays and in print.
+    pub pixel_ratio: f64,
+    /// Painted behind the whole image, including the legend. Charts themselves usually have a
+    /// transparent background, so this is what the image is seen against.
+    pub background: String,
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        Self {
+            width: None,
+            height: None,
+            pixel_ratio: 1.0,
+            background: "white".into(),
+        }
+    }
+}
+
+/// What a mounted chart hands to its [`ChartExport`] so that it can be drawn again off screen.
+pub(crate) struct ExportSource {
+    /// The chart's canvas, used for its current size and to resolve CSS variables in the theme.
+    pub canvas: NodeRef,
+    pub theme: Theme,
+    pub chart: Rc<dyn Chart>,
+}
+
+/// A handle for exporting a chart as a PNG image, e.g. for a "Download chart" button.
+///
+/// Create one with [`use_chart_export`] and pass it to a chart's `export` prop. The chart is
+/// drawn again at the requested size rather than copied from the screen, and includes the
+/// legend.
+///
+///```ignore
+/// let export = use_chart_export();
+/// let onclick = {
+///     let export = export.clone();
+///     Callback::from(move |_| {
+///         export.download("chart.png", &ExportOptions::default());
+///     })
+/// };
+///
+/// html! {
+///     <>
+///         <PieChart data={data} export={export} />
+///         <button {onclick}>{ "Download chart" }</button>
+///     </>
+/// }
+/// ```
+#[derive(Clone, Default)]
+pub struct ChartExport {
+    source: Rc<RefCell<Option<ExportSource>>>,
+}
+
+impl PartialEq for ChartExport {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.source, &other.source)
+    }
+}
+
+impl fmt::Debug for ChartExport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ChartExport")
+            .field("mounted", &self.source.borrow().is_some())
+            .finish()
+    }
+}
+
+impl ChartExport {
+    pub(crate) fn set_source(&self, source: ExportSource) {
+        *self.source.borrow_mut() = Some(source);
+    }
+
+    pub(crate) fn clear(&self) {
+        self.source.borrow_mut().take();
+    }
+
+    /// Draws the chart and its legend onto a new canvas. Returns `None` while no chart using
+    /// this handle is mounted.
+    pub fn to_canvas(&self, options: &ExportOptions) -> Option<HtmlCanvasElement> {
+        let source = self.source.borrow();
+        let source = source.as_ref()?;
+        let chart_canvas = source.canvas.cast::<HtmlCanvasElement>()?;
+
+        let width = options
+            .width
+            .map_or(chart_canvas.client_width() as f64, |width| width as f64);
+        let height = options
+            .height
+            .map_or(chart_canvas.client_height() as f64, |height| height as f64);
+        let theme = match chart_canvas.parent_element() {
+            Some(parent) => source.theme.resolve_css_variables(&parent),
+            None => source.theme.clone(),
+        };
+
+        let canvas = window()?
+            .document()?
+            .create_element("canvas")
+            .ok()?
+            .dyn_into::<HtmlCanvasElement>()
+            .ok()?;
+        let context = canvas
+            .get_context("2d")
+            .ok()??
+            .dyn_into::<CanvasRenderingContext2d>()
+            .ok()?;
+
+        // Lay the legend out first, since it decides how large the image is
+        let legend = source.chart.legend();
+        let legend_layout = LegendLayout::measure(
+            &context,
+            &legend,
+            &source.chart.legend_config(),
+            &theme,
+            (width, height),
+            source.chart.plot_inset(&theme),
+        );
+        let (image_width, image_height) = legend_layout.size;
+
+        canvas.set_width((image_width * options.pixel_ratio).round() as u32);
+        canvas.set_height((image_height * options.pixel_ratio).round() as u32);
+        let _ignored_result =
+            context.set_transform(options.pixel_ratio, 0.0, 0.0, options.pixel_ratio, 0.0, 0.0);
+
+        context.save();
+        let (x, y) = legend_layout.chart;
+        let _ignored_result = context.translate(x, y);
+        source.chart.draw(&context, width, height, &theme);
+        context.restore();
+
+        legend_layout.draw(&context, &legend, &theme);
+
+        // Paint the background last, since charts clear their area before drawing
+        let _ignored_result = context.set_global_composite_operation("destination-over");
+        context.set_fill_style(&JsValue::from_str(&options.background));
+        context.fill_rect(0.0, 0.0, image_width, image_height);
+
+        Some(canvas)
+    }
+
+    /// The exported image as a `data:image/png;base64,...` URL.
+    pub fn to_data_url(&self, options: &ExportOptions) -> Option<String> {
+        self.to_canvas(options)?
+            .to_data_url_with_type("image/png")
+            .ok()
+    }
+
+    /// The exported image as PNG bytes.
+    pub fn to_png(&self, options: &ExportOptions) -> Option<Vec<u8>> {
+        let data_url = self.to_data_url(options)?;
+        let base64 = data_url.strip_prefix("data:image/png;base64,")?;
+        let binary = window()?.atob(base64).ok()?;
+
+        // `atob` returns one character per byte
+        Some(binary.chars().map(|byte| byte as u8).collect())
+    }
+
+    /// Exports the chart and asks the browser to download it as `filename`. Returns whether
+    /// the download was started.
+    pub fn download(&self, filename: &str, options: &ExportOptions) -> bool {
+        let Some(data_url) = self.to_data_url(options) else {
+            return false;
+        };
+
+        let anchor = window()
+            .and_then(|window| window.document())
+            .and_then(|document| document.create_element("a").ok())
+            .and_then(|element| element.dyn_into::<HtmlAnchorElement>().ok());
+        let Some(anchor) = anchor else {
+            return false;
+        };
+
+        anchor.set_href(&data_url);
+        anchor.set_download(filename);
+        anchor.click();
+        true
+    }
+}
+
+/// Keeps the same [`ChartExport`] across renders of a component.
+#[hook]
+pub fn use_chart_export() -> ChartExport {
+    (*use_state(ChartExport::default)).clone()
+}
@@ -0,0 +1,277 @@
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::fmt;
+use std::rc::Rc;
+use yew::prelude::*;
+
+use crate::charts::crosshair::Hover;
+use crate::charts::interaction::CanvasState;
+use crate::charts::zoom::{PlotArea, ViewRange, ZoomAxes};
+
+/// Links the line charts rendered inside a [`ChartGroupProvider`], so that they hover, zoom and
+/// hide series together.
+///
+/// Moving the pointer over one chart moves the crosshair of every chart in the group to the
+/// same point, zooming or panning one shows the same range along the x-axis in all of them,
+/// and clicking a series in a legend hides the series with that name everywhere. Charts only
+/// pass their hover on while their own crosshair is enabled.
+///
+///```ignore
+/// let group = use_chart_group();
+///
+/// html! {
+///     <ChartGroupProvider context={group.clone()}>
+///         <LineCurveChart data={latency} x={times.clone()} config={config.clone()} />
+///         <LineCurveChart data={throughput} x={times} config={config} />
+///         <button onclick={move |_| group.reset_zoom()}>{ "Reset zoom" }</button>
+///     </ChartGroupProvider>
+/// }
+/// ```
+#[derive(Clone, Default)]
+pub struct ChartGroup {
+    inner: Rc<RefCell<GroupState>>,
+}
+
+#[derive(Default)]
+struct GroupState {
+    /// Each chart's latest handles, which it swaps in whenever it renders.
+    members: Vec<Rc<RefCell<GroupMember>>>,
+    hover: Option<usize>,
+    x: Option<(f64, f64)>,
+    hidden: HashSet<String>,
+}
+
+/// Provides a [`ChartGroup`] to every chart rendered inside it.
+pub type ChartGroupProvider = ContextProvider<ChartGroup>;
+
+impl ChartGroup {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The point hovered in any of the charts.
+    pub fn hovered(&self) -> Option<usize> {
+        self.inner.borrow().hover
+    }
+
+    /// The series hidden in every chart, by name.
+    pub fn hidden_series(&self) -> HashSet<String> {
+        self.inner.borrow().hidden.clone()
+    }
+
+    /// Hides the series named `name` in every chart of the group, or shows it again.
+    pub fn toggle_series(&self, name: &str) {
+        let (members, hidden) = {
+            let mut inner = self.inner.borrow_mut();
+            if !inner.hidden.remove(name) {
+                inner.hidden.insert(name.to_string());
+            }
+            (inner.members(), inner.hidden.clone())
+        };
+
+        for member in members {
+            member.hidden.set(hidden.clone());
+        }
+    }
+
+    /// Shows every chart of the group in full again.
+    pub fn reset_zoom(&self) {
+        self.share_zoom(None, None);
+    }
+
+    /// Adds a chart to the group, bringing it up to date with the others.
+    pub(crate) fn join(&self, member: Rc<RefCell<GroupMember>>) {
+        let (hover, x, hidden) = {
+            let inner = self.inner.borrow();
+            (inner.hover, inner.x, inner.hidden.clone())
+        };
+        let current = member.borrow().clone();
+        current.hover(hover);
+        current.zoom(x);
+        current.hidden.set(hidden);
+
+        self.inner.borrow_mut().members.push(member);
+    }
+
+    pub(crate) fn leave(&self, member: &Rc<RefCell<GroupMember>>) {
+        self.inner
+            .borrow_mut()
+            .members
+            .retain(|other| !Rc::ptr_eq(other, member));
+    }
+
+    /// Builds the callback through which the chart with `state` passes the point hovered in it
+    /// on to the rest of the group.
+    pub(crate) fn hover_callback(&self, state: &CanvasState) -> Callback<Option<usize>> {
+        let group = self.clone();
+        let state = state.clone();
+        Callback::from(move |index: Option<usize>| {
+            let members = {
+                let mut inner = group.inner.borrow_mut();
+                inner.hover = index;
+                inner.members()
+            };
+
+            for member in members.iter().filter(|member| !member.state.is(&state)) {
+                member.hover(index);
+            }
+        })
+    }
+
+    /// Builds the callback through which the chart with `state` passes its visible range on to
+    /// the rest of the group, before calling `on_zoom` with it.
+    pub(crate) fn zoom_callback(
+        &self,
+        state: &CanvasState,
+        on_zoom: Option<Callback<ViewRange>>,
+    ) -> Callback<ViewRange> {
+        let group = self.clone();
+        let state = state.clone();
+        Callback::from(move |range: ViewRange| {
+            // The chart has already stored its range, which is `None` when it shows everything
+            group.share_zoom(Some(&state), state.view().map(|view| view.x));
+            if let Some(on_zoom) = &on_zoom {
+                on_zoom.emit(range);
+            }
+        })
+    }
+
+    fn share_zoom(&self, from: Option<&CanvasState>, x: Option<(f64, f64)>) {
+        let members = {
+            let mut inner = self.inner.borrow_mut();
+            inner.x = x;
+            inner.members()
+        };
+
+        for member in members
+            .iter()
+            .filter(|member| from.is_none_or(|from| !member.state.is(from)))
+        {
+            member.zoom(x);
+        }
+    }
+}
+
+impl GroupState {
+    /// The members as they are now, so that updating them can't re-enter the group.
+    fn members(&self) -> Vec<GroupMember> {
+        self.members
+            .iter()
+            .map(|member| member.borrow().clone())
+            .collect()
+    }
+}
+
+impl PartialEq for ChartGroup {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.inner, &other.inner)
+    }
+}
+
+impl fmt::Debug for ChartGroup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let inner = self.inner.borrow();
+        f.debug_struct("ChartGroup")
+            .field("members", &inner.members.len())
+            .field("hover", &inner.hover)
+            .field("x", &inner.x)
+            .field("hidden", &inner.hidden)
+            .finish()
+    }
+}
+
+/// Creates a [`ChartGroup`] that lives as long as the calling component.
+#[hook]
+pub fn use_chart_group() -> ChartGroup {
+    (*use_state(ChartGroup::default)).clone()
+}
+
+/// What a chart hands to its group so that the others can update it.
+#[derive(Clone)]
+pub(crate) struct GroupMember {
+    pub state: CanvasState,
+    pub area: PlotArea,
+    /// The hovered point, for re-rendering the tooltip.
+    pub hovered: UseStateHandle<Option<usize>>,
+    pub zoomed: UseStateHandle<bool>,
+    /// The names of the series left out of the chart.
+    pub hidden: UseStateHandle<HashSet<String>>,
+}
+
+impl GroupMember {
+    fn hover(&self, index: Option<usize>) {
+        if self.state.hover().map(|hover| hover.index) != index {
+            // Values don't carry over between charts, so no horizontal guide is drawn
+            self.state.set_hover(index.map(|index| Hover {
+                index,
+                value: f64::NAN,
+            }));
+            self.state.redraw();
+        }
+        self.hovered.set(index);
+    }
+
+    fn zoom(&self, x: Option<(f64, f64)>) {
+        let (width, height) = self.state.size();
+        let (_, full) = (self.area)(width, height);
+        if full.x.1 <= full.x.0 {
+            return;
+        }
+
+        // Only the x-axis is shared, since the charts show different values
+        let current = self.state.view().unwrap_or(full);
+        let view =
+            ViewRange::new(x.unwrap_or(full.x), current.y).pan((0.0, 0.0), ZoomAxes::X, &full);
+        let view = Some(view).filter(|view| *view != full);
+        if view != self.state.view() {
+            self.state.set_view(view);
+            self.state.redraw();
+        }
+        self.zoomed.set(view.is_some());
+    }
+}
+
+/// Keeps the chart described by `member` in the group it is rendered in, if any, for as long
+/// as it is mounted.
+#[hook]
+pub(crate) fn use_group_member(member: GroupMember) -> Option<ChartGroup> {
+    let group = use_context::<ChartGroup>();
+    let current = use_mut_ref(|| member.clone());
+    *current.borrow_mut() = member;
+
+    // Joins once, while the group keeps seeing the handles of the latest render through
+    // `current`
+    use_effect_with(group.clone(), move |group| {
+        let group = group.clone();
+        if let Some(group) = &group {
+            group.join(current.clone());
+        }
+
+        move || {
+            if let Some(group) = group {
+                group.leave(&current);
+            }
+        }
+    });
+
+    group
+}
+
+/// Hides the series named `name`, or shows it again, in the whole group if the chart is in one
+/// and otherwise only in the chart whose `hidden` series these are.
+pub(crate) fn toggle_series(
+    group: Option<&ChartGroup>,
+    hidden: &UseStateHandle<HashSet<String>>,
+    name: &str,
+) {
+    match group {
+        Some(group) => group.toggle_series(name),
+        None => {
+            let mut series = (**hidden).clone();
+            if !series.remove(name) {
+                series.insert(name.to_string());
+            }
+            hidden.set(series);
+        }
+    }
+}
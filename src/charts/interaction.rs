@@ -0,0 +1,326 @@
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use web_sys::HtmlElement;
+use yew::prelude::*;
+
+use crate::charts::accessibility::VISUALLY_HIDDEN;
+use crate::charts::crosshair::Hover;
+use crate::charts::theme::Theme;
+use crate::charts::zoom::ViewRange;
+
+/// Identifies a single data point: a bar, a slice or a point on a line.
+///
+/// Charts with a single series always use series `0`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PointIndex {
+    pub series: usize,
+    pub index: usize,
+}
+
+impl PointIndex {
+    pub fn new(series: usize, index: usize) -> Self {
+        Self { series, index }
+    }
+}
+
+type RedrawFn = Rc<dyn Fn()>;
+
+/// The state of the user's interaction with a chart that its scene is laid out with.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub(crate) struct Interactions {
+    /// The data point selected with the keyboard.
+    pub focused: Option<PointIndex>,
+    /// The part of the data zoomed into, or `None` when the whole chart is shown.
+    pub view: Option<ViewRange>,
+    /// The brushed range along the x-axis, in data units.
+    pub brush: Option<(f64, f64)>,
+    /// Where the pointer is over the plot area.
+    pub hover: Option<Hover>,
+}
+
+/// State shared between a chart's drawing effect and its event handlers, without causing
+/// re-renders.
+#[derive(Clone, Default)]
+pub(crate) struct CanvasState {
+    redraw: Rc<RefCell<Option<RedrawFn>>>,
+    size: Rc<Cell<(f64, f64)>>,
+    focus: Rc<Cell<Option<PointIndex>>>,
+    view: Rc<Cell<Option<ViewRange>>>,
+    brush: Rc<Cell<Option<(f64, f64)>>>,
+    hover: Rc<Cell<Option<Hover>>>,
+}
+
+impl CanvasState {
+    /// Registers the function that redraws the chart with its current data.
+    pub fn set_redraw(&self, redraw: RedrawFn) {
+        *self.redraw.borrow_mut() = Some(redraw);
+    }
+
+    pub fn redraw(&self) {
+        let redraw = self.redraw.borrow().clone();
+        if let Some(redraw) = redraw {
+            redraw();
+        }
+    }
+
+    /// Whether `other` is the state of the same chart.
+    pub fn is(&self, other: &CanvasState) -> bool {
+        Rc::ptr_eq(&self.size, &other.size)
+    }
+
+    /// The size the chart was last drawn at, in CSS pixels.
+    pub fn size(&self) -> (f64, f64) {
+        self.size.get()
+    }
+
+    pub fn set_size(&self, width: f64, height: f64) {
+        self.size.set((width, height));
+    }
+
+    /// The data point currently selected with the keyboard.
+    pub fn focus(&self) -> Option<PointIndex> {
+        self.focus.get()
+    }
+
+    /// The part of the data zoomed into, or `None` when the whole chart is shown.
+    pub fn view(&self) -> Option<ViewRange> {
+        self.view.get()
+    }
+
+    pub fn set_view(&self, view: Option<ViewRange>) {
+        self.view.set(view);
+    }
+
+    /// The brushed range along the x-axis, in data units.
+    pub fn brush(&self) -> Option<(f64, f64)> {
+        self.brush.get()
+    }
+
+    pub fn set_brush(&self, brush: Option<(f64, f64)>) {
+        self.brush.set(brush);
+    }
+
+    /// Where the pointer is over the plot area.
+    pub fn hover(&self) -> Option<Hover> {
+        self.hover.get()
+    }
+
+    pub fn set_hover(&self, hover: Option<Hover>) {
+        self.hover.set(hover);
+    }
+
+    /// Everything the user is doing with the chart that shows up in its drawing.
+    pub fn interactions(&self) -> Interactions {
+        Interactions {
+            focused: self.focus(),
+            view: self.view(),
+            brush: self.brush(),
+            hover: self.hover(),
+        }
+    }
+}
+
+#[hook]
+pub(crate) fn use_canvas_state() -> CanvasState {
+    (*use_state(CanvasState::default)).clone()
+}
+
+/// Builds the `keydown` handler that moves the keyboard focus through the chart's data points.
+///
+/// `series_lengths` holds the number of points in each series. Moving the focus redraws the
+/// chart so that the focused point is highlighted, and `Enter` or `Space` passes the focused
+/// point to `on_activate`.
+pub(crate) fn keyboard_navigation(
+    state: &CanvasState,
+    focus: &UseStateHandle<Option<PointIndex>>,
+    series_lengths: Vec<usize>,
+    on_activate: Option<Callback<PointIndex>>,
+) -> Callback<KeyboardEvent> {
+    let state = state.clone();
+    let focus = focus.clone();
+
+    Callback::from(move |event: KeyboardEvent| {
+        let key = event.key();
+
+        if key == "Enter" || key == " " {
+            if let (Some(point), Some(on_activate)) = (state.focus(), &on_activate) {
+                event.prevent_default();
+                on_activate.emit(point);
+            }
+            return;
+        }
+
+        if let Some(next) = next_focus(state.focus(), &key, &series_lengths) {
+            event.prevent_default();
+            set_focus(&state, &focus, next);
+        }
+    })
+}
+
+/// Builds the `blur` handler that hides the keyboard focus when the chart loses focus.
+pub(crate) fn clear_focus_on_blur(
+    state: &CanvasState,
+    focus: &UseStateHandle<Option<PointIndex>>,
+) -> Callback<FocusEvent> {
+    let state = state.clone();
+    let focus = focus.clone();
+
+    Callback::from(move |_event: FocusEvent| {
+        if state.focus().is_some() {
+            set_focus(&state, &focus, None);
+        }
+    })
+}
+
+fn set_focus(
+    state: &CanvasState,
+    focus: &UseStateHandle<Option<PointIndex>>,
+    point: Option<PointIndex>,
+) {
+    state.focus.set(point);
+    focus.set(point);
+    state.redraw();
+}
+
+/// Works out where the focus moves for a key press. Returns `None` for keys that don't
+/// navigate, and `Some(None)` when the focus is cleared.
+///
+/// Left/Right step through the points of a series and Up/Down switch between series. Charts
+/// with a single series step through their points with all four arrow keys.
+pub(crate) fn next_focus(
+    current: Option<PointIndex>,
+    key: &str,
+    series_lengths: &[usize],
+) -> Option<Option<PointIndex>> {
+    if key == "Escape" {
+        return Some(None);
+    }
+
+    let key = match (key, series_lengths.len()) {
+        ("ArrowDown", 1) => "ArrowRight",
+        ("ArrowUp", 1) => "ArrowLeft",
+        (key, _) => key,
+    };
+    if !matches!(
+        key,
+        "ArrowLeft" | "ArrowRight" | "ArrowUp" | "ArrowDown" | "Home" | "End"
+    ) {
+        return None;
+    }
+
+    let Some(current) = current else {
+        // Start on the first point of the first series that has any
+        let series = series_lengths.iter().position(|&length| length > 0)?;
+        let index = if key == "End" {
+            series_lengths[series] - 1
+        } else {
+            0
+        };
+        return Some(Some(PointIndex::new(series, index)));
+    };
+
+    let last_index = series_lengths.get(current.series)?.checked_sub(1)?;
+    let next = match key {
+        "ArrowLeft" => PointIndex::new(current.series, current.index.saturating_sub(1)),
+        "ArrowRight" => PointIndex::new(current.series, (current.index + 1).min(last_index)),
+        "Home" => PointIndex::new(current.series, 0),
+        "End" => PointIndex::new(current.series, last_index),
+        _ => {
+            // Switch to the nearest series that has a point to land on
+            let mut series = current.series;
+            loop {
+                series = match key {
+                    "ArrowUp" => series.checked_sub(1),
+                    _ => Some(series + 1).filter(|&series| series < series_lengths.len()),
+                }
+                .unwrap_or(current.series);
+
+                if series == current.series || series_lengths[series] > 0 {
+                    break;
+                }
+            }
+            let index = current.index.min(series_lengths[series].saturating_sub(1));
+            PointIndex::new(series, index)
+        }
+    };
+
+    Some(Some(next))
+}
+
+/// The canvas' position inside the chart's container, used to place HTML over the canvas.
+pub(crate) fn canvas_offset(canvas_ref: &NodeRef) -> (f64, f64) {
+    canvas_ref
+        .cast::<HtmlElement>()
+        .map(|canvas| (canvas.offset_left() as f64, canvas.offset_top() as f64))
+        .unwrap_or_default()
+}
+
+/// A tooltip anchored at `(x, y)` relative to the chart's container, drawn above the anchor.
+pub(crate) fn tooltip(theme: &Theme, x: f64, y: f64, text: &str) -> Html {
+    let style = format!(
+        "position: absolute; left: {}px; top: {}px; transform: translate(-50%, calc(-100% - 8px)); \
+         pointer-events: none; padding: 4px 8px; border-radius: 4px; white-space: nowrap; \
+         font: {}; background: {}; color: {};",
+        x, y, theme.font, theme.tooltip_background, theme.tooltip_text_color
+    );
+
+    html! {
+        <div style={style} aria-hidden="true">{ text }</div>
+    }
+}
+
+/// Announces `text` to screen readers whenever it changes.
+pub(crate) fn live_region(text: &str) -> Html {
+    html! {
+        <div aria-live="polite" aria-atomic="true" style={VISUALLY_HIDDEN}>{ text }</div>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(series: usize, index: usize) -> Option<Option<PointIndex>> {
+        Some(Some(PointIndex::new(series, index)))
+    }
+
+    #[test]
+    fn first_key_press_focuses_first_point() {
+        assert_eq!(next_focus(None, "ArrowRight", &[3, 3]), point(0, 0));
+        assert_eq!(next_focus(None, "End", &[0, 4]), point(1, 3));
+        assert_eq!(next_focus(None, "ArrowRight", &[0]), None);
+    }
+
+    #[test]
+    fn left_and_right_stay_within_the_series() {
+        let current = Some(PointIndex::new(0, 2));
+
+        assert_eq!(next_focus(current, "ArrowRight", &[3]), point(0, 2));
+        assert_eq!(next_focus(current, "ArrowLeft", &[3]), point(0, 1));
+        assert_eq!(next_focus(current, "Home", &[3]), point(0, 0));
+    }
+
+    #[test]
+    fn up_and_down_switch_series() {
+        let current = Some(PointIndex::new(0, 4));
+
+        assert_eq!(next_focus(current, "ArrowDown", &[5, 0, 3]), point(2, 2));
+        assert_eq!(next_focus(current, "ArrowUp", &[5, 0, 3]), point(0, 4));
+    }
+
+    #[test]
+    fn up_and_down_step_through_single_series() {
+        let current = Some(PointIndex::new(0, 1));
+
+        assert_eq!(next_focus(current, "ArrowDown", &[3]), point(0, 2));
+        assert_eq!(next_focus(current, "ArrowUp", &[3]), point(0, 0));
+    }
+
+    #[test]
+    fn escape_clears_and_other_keys_are_ignored() {
+        let current = Some(PointIndex::new(0, 1));
+
+        assert_eq!(next_focus(current, "Escape", &[3]), Some(None));
+        assert_eq!(next_focus(current, "a", &[3]), None);
+    }
+}
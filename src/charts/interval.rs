@@ -0,0 +1,93 @@
+use crate::charts::scene::{Line, SceneItem, Stroke};
+
+/// The range a data point's value is uncertain within, such as a confidence interval.
+///
+///```
+/// # use visualize_yew::interval::Interval;
+/// assert_eq!(Interval::Error(3).bounds(10), (7, 13));
+/// assert_eq!(Interval::Bounds(8, 15).bounds(10), (8, 15));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Interval {
+    /// The same distance below and above the value.
+    Error(i32),
+    /// The lower and upper bound.
+    Bounds(i32, i32),
+}
+
+impl Interval {
+    /// The lower and upper bound around `value`.
+    pub fn bounds(&self, value: i32) -> (i32, i32) {
+        match *self {
+            Interval::Error(error) => (
+                value.saturating_sub_unsigned(error.unsigned_abs()),
+                value.saturating_add_unsigned(error.unsigned_abs()),
+            ),
+            Interval::Bounds(lower, upper) => (lower.min(upper), lower.max(upper)),
+        }
+    }
+
+    /// `value` together with the interval, the way tooltips show it.
+    pub(crate) fn describe(&self, value: i32) -> String {
+        match *self {
+            Interval::Error(error) => format!("{} ± {}", value, error.unsigned_abs()),
+            Interval::Bounds(..) => {
+                let (lower, upper) = self.bounds(value);
+                format!("{} ({}–{})", value, lower, upper)
+            }
+        }
+    }
+}
+
+/// `value` with its interval, if it has one.
+pub(crate) fn describe(value: i32, interval: Option<Interval>) -> String {
+    match interval {
+        Some(interval) => interval.describe(value),
+        None => value.to_string(),
+    }
+}
+
+/// A whisker at `x` from `top` to `bottom`, with caps `cap_width` wide at both ends.
+pub(crate) fn whisker(
+    x: f64,
+    (top, bottom): (f64, f64),
+    cap_width: f64,
+    stroke: &Stroke,
+) -> Vec<SceneItem> {
+    let line = |from: (f64, f64), to: (f64, f64)| {
+        SceneItem::Line(Line {
+            from,
+            to,
+            stroke: stroke.clone(),
+            dash: Vec::new(),
+        })
+    };
+    let half = cap_width / 2.0;
+
+    vec![
+        line((x, top), (x, bottom)),
+        line((x - half, top), (x + half, top)),
+        line((x - half, bottom), (x + half, bottom)),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn describes_errors_and_bounds() {
+        assert_eq!(describe(12, Some(Interval::Error(-2))), "12 ± 2");
+        assert_eq!(describe(12, Some(Interval::Bounds(15, 9))), "12 (9–15)");
+        assert_eq!(describe(12, None), "12");
+        assert_eq!(
+            describe(0, Some(Interval::Error(i32::MIN))),
+            "0 ± 2147483648"
+        );
+        assert_eq!(Interval::Error(i32::MIN).bounds(0), (i32::MIN, i32::MAX));
+        assert_eq!(
+            Interval::Error(10).bounds(i32::MAX - 5),
+            (i32::MAX - 15, i32::MAX)
+        );
+    }
+}
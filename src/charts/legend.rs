@@ -0,0 +1,900 @@
+use std::f64::consts::PI;
+use web_sys::Element;
+use yew::prelude::*;
+
+use crate::charts::canvas::Canvas;
+use crate::charts::pattern::{swatch_background, Pattern};
+use crate::charts::resize::listen_for_element_resize;
+use crate::charts::scene::Bounds;
+use crate::charts::text::estimated_text_width;
+use crate::charts::theme::Theme;
+
+/// How a chart's legend is laid out: where it goes, how its entries are aligned, how many rows
+/// it may take up and what its swatches look like.
+///
+///```
+/// # use visualize_yew::legend::{Corner, LegendConfig, LegendOverflow, LegendPosition, SwatchShape};
+/// let legend = LegendConfig::default()
+///     .with_position(LegendPosition::Inside(Corner::TopLeft))
+///     .with_max_rows(3, LegendOverflow::More)
+///     .with_swatch(SwatchShape::Line);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct LegendConfig {
+    pub position: LegendPosition,
+    /// Where the entries sit along the legend: across the chart when it is above or below it,
+    /// down the side otherwise.
+    pub align: LegendAlign,
+    /// The most rows of entries shown at once, or 0 for no limit. When the legend is beside or
+    /// inside the chart, every entry is a row of its own.
+    pub max_rows: u32,
+    /// What happens to the entries beyond `max_rows`.
+    pub overflow: LegendOverflow,
+    pub swatch: SwatchShape,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum LegendPosition {
+    #[default]
+    Top,
+    Bottom,
+    Left,
+    Right,
+    /// Over the chart, in a corner of the plot.
+    Inside(Corner),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Corner {
+    TopLeft,
+    #[default]
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum LegendAlign {
+    #[default]
+    Start,
+    Center,
+    End,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum LegendOverflow {
+    /// The legend scrolls to the remaining entries.
+    #[default]
+    Scroll,
+    /// The remaining entries are left out and counted instead, e.g. "+4 more".
+    More,
+}
+
+/// The mark drawn next to each legend label in the entry's color.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum SwatchShape {
+    #[default]
+    Square,
+    Circle,
+    /// A short horizontal line, which suits line series.
+    Line,
+}
+
+/// One entry of a chart's legend: a color swatch followed by a label.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LegendEntry {
+    pub label: String,
+    /// Left empty to use the theme's palette color for the entry's index, like data points.
+    pub color: String,
+    pub pattern: Option<Pattern>,
+    pub swatch: SwatchShape,
+}
+
+/// A legend entry as handed to a chart's `legend_label` and `render_legend` props.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LegendItem {
+    /// The position of the series or data point in the chart's data.
+    pub index: usize,
+    /// The name of the series or data point.
+    pub name: String,
+    /// The color it is drawn in, with the theme's palette already applied.
+    pub color: String,
+    pub pattern: Option<Pattern>,
+    /// The data point's value, for charts with one value per entry.
+    pub value: Option<f64>,
+    /// The data point's share of the total, from 0 to 1, for charts with one value per entry.
+    pub share: Option<f64>,
+    /// Whether the series has been hidden by clicking its legend entry.
+    pub hidden: bool,
+}
+
+/// What a chart's `render_legend` prop is given to draw a legend of its own.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LegendContext {
+    pub items: Vec<LegendItem>,
+    /// Hides a series or shows it again, for charts whose series can be hidden.
+    pub on_toggle: Option<Callback<LegendItem>>,
+}
+
+const SWATCH_SIZE: f64 = 10.0;
+const LINE_SWATCH_WIDTH: f64 = 14.0;
+const ROW_HEIGHT: f64 = 14.0;
+const ENTRY_GAP: f64 = 5.0;
+const LABEL_GAP: f64 = 2.0;
+/// Left and right of a legend beside the chart.
+const COLUMN_PADDING: f64 = 5.0;
+/// Around a legend inside the chart.
+const INSIDE_PADDING: f64 = 4.0;
+
+impl LegendConfig {
+    pub fn with_position(mut self, position: LegendPosition) -> Self {
+        self.position = position;
+        self
+    }
+
+    pub fn with_align(mut self, align: LegendAlign) -> Self {
+        self.align = align;
+        self
+    }
+
+    pub fn with_max_rows(mut self, max_rows: u32, overflow: LegendOverflow) -> Self {
+        self.max_rows = max_rows;
+        self.overflow = overflow;
+        self
+    }
+
+    pub fn with_swatch(mut self, swatch: SwatchShape) -> Self {
+        self.swatch = swatch;
+        self
+    }
+
+    /// Whether entries flow across the chart in rows, rather than down a column.
+    fn is_horizontal(&self) -> bool {
+        matches!(self.position, LegendPosition::Top | LegendPosition::Bottom)
+    }
+
+    /// Whether some entries may be left out, which takes the legend's width to work out.
+    fn leaves_entries_out(&self) -> bool {
+        self.max_rows > 0 && self.overflow == LegendOverflow::More
+    }
+
+    /// How many of the entries with `label_widths` fit in `max_rows`, keeping a place for the
+    /// count of the rest when they don't all fit, but always at least one entry. `width` is
+    /// that of the legend, if known yet.
+    pub(crate) fn visible_entries(&self, label_widths: &[f64], width: Option<f64>) -> usize {
+        let count = label_widths.len();
+        if !self.leaves_entries_out() {
+            return count;
+        }
+        let max_rows = self.max_rows as usize;
+
+        if !self.is_horizontal() {
+            return match count > max_rows {
+                true => (max_rows - 1).max(1),
+                false => count,
+            };
+        }
+
+        let Some(width) = width else {
+            return count;
+        };
+        let fitting = flow(&self.entry_widths(label_widths), width)
+            .iter()
+            .take_while(|(_, y)| *y < max_rows as f64 * ROW_HEIGHT)
+            .count();
+        match fitting < count {
+            true => fitting.saturating_sub(1).max(1),
+            false => count,
+        }
+    }
+
+    /// The widths of the entries with `label_widths`, swatch included.
+    fn entry_widths(&self, label_widths: &[f64]) -> Vec<f64> {
+        label_widths
+            .iter()
+            .map(|label_width| self.swatch.width() + LABEL_GAP + label_width)
+            .collect()
+    }
+
+    /// The style of the element holding the entries.
+    fn style(&self, theme: &Theme, (top, right, bottom, left): (f64, f64, f64, f64)) -> String {
+        let align = match self.align {
+            LegendAlign::Start => "flex-start",
+            LegendAlign::Center => "center",
+            LegendAlign::End => "flex-end",
+        };
+        let scroll = match (self.max_rows, self.overflow) {
+            (0, _) | (_, LegendOverflow::More) => String::new(),
+            (rows, LegendOverflow::Scroll) => format!(
+                " max-height: {}px; overflow-y: auto;",
+                rows as f64 * (ROW_HEIGHT + ENTRY_GAP) - ENTRY_GAP
+            ),
+        };
+
+        let layout = match self.position {
+            LegendPosition::Top | LegendPosition::Bottom => format!(
+                "flex-direction: row; flex-wrap: wrap; justify-content: {};",
+                align
+            ),
+            LegendPosition::Left | LegendPosition::Right => format!(
+                "flex-direction: column; justify-content: {}; padding: 0 {}px;",
+                align, COLUMN_PADDING
+            ),
+            LegendPosition::Inside(corner) => {
+                let (vertical, horizontal) = match corner {
+                    Corner::TopLeft => (format!("top: {}px;", top), format!("left: {}px;", left)),
+                    Corner::TopRight => {
+                        (format!("top: {}px;", top), format!("right: {}px;", right))
+                    }
+                    Corner::BottomLeft => (
+                        format!("bottom: {}px;", bottom),
+                        format!("left: {}px;", left),
+                    ),
+                    Corner::BottomRight => (
+                        format!("bottom: {}px;", bottom),
+                        format!("right: {}px;", right),
+                    ),
+                };
+                format!(
+                    "position: absolute; {} {} flex-direction: column; align-items: {}; padding: {}px; background: {};",
+                    vertical, horizontal, align, INSIDE_PADDING, theme.background
+                )
+            }
+        };
+
+        format!("display: flex; gap: {}px; {}{}", ENTRY_GAP, layout, scroll)
+    }
+}
+
+impl SwatchShape {
+    fn width(&self) -> f64 {
+        match self {
+            SwatchShape::Line => LINE_SWATCH_WIDTH,
+            _ => SWATCH_SIZE,
+        }
+    }
+
+    /// The style of the swatch in the HTML legend.
+    fn style(&self, color: &str, pattern: Option<Pattern>, ink: &str) -> String {
+        match self {
+            SwatchShape::Square => format!(
+                "{} width: 10px; height: 10px; display: inline-block;",
+                swatch_background(color, pattern, ink)
+            ),
+            SwatchShape::Circle => format!(
+                "{} width: 10px; height: 10px; display: inline-block; border-radius: 50%;",
+                swatch_background(color, pattern, ink)
+            ),
+            SwatchShape::Line => format!(
+                "{} width: {}px; height: 2px; display: inline-block;",
+                swatch_background(color, None, ink),
+                LINE_SWATCH_WIDTH
+            ),
+        }
+    }
+}
+
+impl LegendItem {
+    /// The label shown for the entry, formatted by `format` if given.
+    pub(crate) fn label(&self, format: Option<&Callback<LegendItem, String>>) -> String {
+        match format {
+            Some(format) => format.emit(self.clone()),
+            None => self.name.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Properties)]
+pub(crate) struct LegendProps {
+    pub config: LegendConfig,
+    pub items: Vec<LegendItem>,
+    pub theme: Theme,
+    /// Formats each entry's label.
+    #[prop_or_default]
+    pub label: Option<Callback<LegendItem, String>>,
+    /// Makes the entries buttons that hide their series or show it again.
+    #[prop_or_default]
+    pub on_toggle: Option<Callback<LegendItem>>,
+    /// Draws the whole legend instead.
+    #[prop_or_default]
+    pub render: Option<Callback<LegendContext, Html>>,
+    /// The distance from the top, right, bottom and left edges of the chart to its plot, which
+    /// a legend inside the chart is placed within.
+    pub inset: (f64, f64, f64, f64),
+}
+
+/// The HTML legend shown with a chart.
+#[function_component]
+pub(crate) fn Legend(props: &LegendProps) -> Html {
+    let root = use_node_ref();
+    let width = use_state_eq(|| None::<f64>);
+    let style = props.config.style(&props.theme, props.inset);
+
+    // Working out which entries fit across the legend takes its width, kept up to date here
+    // for as long as it's needed
+    {
+        let root = root.clone();
+        let width = width.clone();
+        let measures = props.render.is_none()
+            && props.config.leaves_entries_out()
+            && props.config.is_horizontal();
+        use_effect_with(measures, move |&measures| {
+            let listener = root.cast::<Element>().filter(|_| measures).map(|element| {
+                let measure = {
+                    let element = element.clone();
+                    move || width.set(Some(element.client_width() as f64))
+                };
+                measure();
+                listen_for_element_resize(&element, measure)
+            });
+            move || drop(listener)
+        });
+    }
+
+    if let Some(render) = &props.render {
+        let context = LegendContext {
+            items: props.items.clone(),
+            on_toggle: props.on_toggle.clone(),
+        };
+        return html! {
+            <div ref={root} data-chart-legend="" {style}>{ render.emit(context) }</div>
+        };
+    }
+
+    let theme = &props.theme;
+    let labels = props
+        .items
+        .iter()
+        .map(|item| item.label(props.label.as_ref()))
+        .collect::<Vec<_>>();
+    let label_widths = labels
+        .iter()
+        .map(|label| estimated_text_width(&theme.font, label))
+        .collect::<Vec<_>>();
+    let visible = props.config.visible_entries(&label_widths, *width);
+    // Labels are measured in the theme's font above, so they are drawn in it too
+    let label_style = format!("font: {}; color: {};", theme.font, theme.text_color);
+    let entry_style = format!(
+        "display: flex; flex-direction: row; align-items: center; gap: {}px; height: {}px; flex-shrink: 0;",
+        LABEL_GAP, ROW_HEIGHT
+    );
+
+    let entries = props.items.iter().zip(labels).take(visible).map(|(item, label)| {
+        let swatch = html! {
+            <div style={props.config.swatch.style(&item.color, item.pattern, &theme.pattern_color)}></div>
+        };
+        match &props.on_toggle {
+            Some(on_toggle) => {
+                let onclick = {
+                    let on_toggle = on_toggle.clone();
+                    let item = item.clone();
+                    Callback::from(move |_event: MouseEvent| on_toggle.emit(item.clone()))
+                };
+                html! {
+                    <button
+                        type="button"
+                        aria-pressed={(!item.hidden).to_string()}
+                        style={format!(
+                            "{} padding: 0; border: none; background: none; cursor: pointer; {}",
+                            entry_style,
+                            if item.hidden { "opacity: 0.4; text-decoration: line-through;" } else { "" }
+                        )}
+                        {onclick}
+                    >
+                        { swatch }
+                        <span style={label_style.clone()}>{ label }</span>
+                    </button>
+                }
+            }
+            None => html! {
+                <div style={entry_style.clone()}>
+                    { swatch }
+                    <span style={label_style.clone()}>{ label }</span>
+                </div>
+            },
+        }
+    });
+
+    let more = props.items.len() - visible;
+    let more_html = if more > 0 {
+        html! {
+            <span style={format!("{} {}", entry_style, label_style)}>{ format!("+{} more", more) }</span>
+        }
+    } else {
+        html! {}
+    };
+
+    html! {
+        <div ref={root} data-chart-legend="" {style}>
+            { for entries }
+            { more_html }
+        </div>
+    }
+}
+
+/// Places `legend` around `canvas` as `position` says. A legend inside the chart is positioned
+/// absolutely, so it only has to come before the canvas.
+pub(crate) fn with_legend(position: LegendPosition, legend: Html, canvas: Html) -> Html {
+    let beside = |first: Html, second: Html| {
+        html! {
+            <div style="display: flex; flex-direction: row; height: 100%;">
+                { first }
+                { second }
+            </div>
+        }
+    };
+    let canvas_column = |canvas: Html| {
+        html! { <div style="flex: 1; min-width: 0;">{ canvas }</div> }
+    };
+
+    match position {
+        LegendPosition::Top | LegendPosition::Inside(_) => html! { <>{ legend }{ canvas }</> },
+        LegendPosition::Bottom => html! { <>{ canvas }{ legend }</> },
+        LegendPosition::Left => beside(legend, canvas_column(canvas)),
+        LegendPosition::Right => beside(canvas_column(canvas), legend),
+    }
+}
+
+/// The top-left corner of each entry when entries `widths` wide flow left to right across
+/// `max_width`, wrapping onto a new row when an entry doesn't fit.
+fn flow(widths: &[f64], max_width: f64) -> Vec<(f64, f64)> {
+    let mut positions = Vec::with_capacity(widths.len());
+    let (mut x, mut y) = (0.0, 0.0);
+
+    for width in widths {
+        if x > 0.0 && x + width > max_width {
+            x = 0.0;
+            y += ROW_HEIGHT;
+        }
+
+        positions.push((x, y));
+        x += width + ENTRY_GAP;
+    }
+    positions
+}
+
+/// Where a chart's legend and the chart itself go when both are drawn onto one canvas, e.g.
+/// for exports.
+///
+/// The legend is placed like the HTML legend, following its [`LegendConfig`]. Above or below
+/// the chart, entries flow left to right and wrap onto a new row when they don't fit, and the
+/// image grows taller to make room for them. Beside the chart, the image grows wider for a
+/// column of entries, and inside it the entries are drawn over a corner of the plot. Entries
+/// beyond `max_rows` are left out, and counted if the overflow says so.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct LegendLayout {
+    /// The top-left corner of each entry shown.
+    positions: Vec<(f64, f64)>,
+    /// The count of the entries left out, and the top-left corner it is drawn at.
+    more: Option<(String, (f64, f64))>,
+    /// What a legend drawn over the chart is filled in behind its entries.
+    backdrop: Option<Bounds>,
+    /// Where the top-left corner of the chart goes.
+    pub chart: (f64, f64),
+    /// The size of the chart and legend together.
+    pub size: (f64, f64),
+}
+
+impl LegendLayout {
+    /// Measures the labels of `entries` in the theme's font and places them around a chart of
+    /// `size`, whose plot is `inset` from its top, right, bottom and left edges.
+    pub fn measure(
+        canvas: &dyn Canvas,
+        entries: &[LegendEntry],
+        config: &LegendConfig,
+        theme: &Theme,
+        size: (f64, f64),
+        inset: (f64, f64, f64, f64),
+    ) -> Self {
+        canvas.set_font(&theme.font);
+        let label_widths = entries
+            .iter()
+            .map(|entry| canvas.measure_text(&entry.label))
+            .collect::<Vec<_>>();
+
+        Self::new(
+            config,
+            &label_widths,
+            |text| canvas.measure_text(text),
+            size,
+            inset,
+        )
+    }
+
+    fn new(
+        config: &LegendConfig,
+        label_widths: &[f64],
+        measure: impl Fn(&str) -> f64,
+        (width, height): (f64, f64),
+        (top, right, bottom, left): (f64, f64, f64, f64),
+    ) -> Self {
+        let visible = config.visible_entries(label_widths, Some(width));
+        let mut widths = config.entry_widths(&label_widths[..visible]);
+        let more = (visible < label_widths.len())
+            .then(|| format!("+{} more", label_widths.len() - visible));
+        if let Some(more) = &more {
+            widths.push(measure(more));
+        }
+
+        // Lay the entries out from the legend's top-left corner
+        let mut positions = match config.is_horizontal() {
+            true => flow(&widths, width),
+            false => (0..widths.len())
+                .map(|i| (0.0, i as f64 * ROW_HEIGHT))
+                .collect(),
+        };
+        // A scrolling legend shows the rows on screen before it is scrolled
+        if config.max_rows > 0 && config.overflow == LegendOverflow::Scroll {
+            positions.retain(|&(_, y)| y < config.max_rows as f64 * ROW_HEIGHT);
+        }
+        let legend_height = positions.last().map_or(0.0, |(_, y)| y + ROW_HEIGHT);
+        let column_width = widths
+            .iter()
+            .take(positions.len())
+            .copied()
+            .fold(0.0, f64::max);
+
+        let share = match config.align {
+            LegendAlign::Start => 0.0,
+            LegendAlign::Center => 0.5,
+            LegendAlign::End => 1.0,
+        };
+        match config.position {
+            // Each row is aligned across the chart
+            LegendPosition::Top | LegendPosition::Bottom => {
+                let mut row_start = 0;
+                for i in 0..positions.len() {
+                    let row_end = positions
+                        .get(i + 1)
+                        .is_none_or(|next| next.1 > positions[i].1);
+                    if row_end {
+                        let row_width = positions[i].0 + widths[i];
+                        for position in &mut positions[row_start..=i] {
+                            position.0 += (width - row_width).max(0.0) * share;
+                        }
+                        row_start = i + 1;
+                    }
+                }
+            }
+            // The column is aligned down the side of the chart
+            LegendPosition::Left | LegendPosition::Right => {
+                for position in &mut positions {
+                    position.1 += (height - legend_height).max(0.0) * share;
+                }
+            }
+            // Entries are aligned within the box they are drawn in
+            LegendPosition::Inside(_) => {
+                for (position, entry_width) in positions.iter_mut().zip(&widths) {
+                    position.0 += (column_width - entry_width) * share;
+                }
+            }
+        }
+
+        // Make room for the legend next to the chart, or place it over the chart's plot
+        let beside_width = column_width + 2.0 * COLUMN_PADDING;
+        let (origin, chart, size, backdrop) = match config.position {
+            _ if positions.is_empty() => ((0.0, 0.0), (0.0, 0.0), (width, height), None),
+            LegendPosition::Top => (
+                (0.0, 0.0),
+                (0.0, legend_height),
+                (width, height + legend_height),
+                None,
+            ),
+            LegendPosition::Bottom => (
+                (0.0, height),
+                (0.0, 0.0),
+                (width, height + legend_height),
+                None,
+            ),
+            LegendPosition::Left => (
+                (COLUMN_PADDING, 0.0),
+                (beside_width, 0.0),
+                (width + beside_width, height),
+                None,
+            ),
+            LegendPosition::Right => (
+                (width + COLUMN_PADDING, 0.0),
+                (0.0, 0.0),
+                (width + beside_width, height),
+                None,
+            ),
+            LegendPosition::Inside(corner) => {
+                let box_width = column_width + 2.0 * INSIDE_PADDING;
+                let box_height = legend_height + 2.0 * INSIDE_PADDING;
+                let x = match corner {
+                    Corner::TopLeft | Corner::BottomLeft => left,
+                    Corner::TopRight | Corner::BottomRight => width - right - box_width,
+                };
+                let y = match corner {
+                    Corner::TopLeft | Corner::TopRight => top,
+                    Corner::BottomLeft | Corner::BottomRight => height - bottom - box_height,
+                };
+                (
+                    (x + INSIDE_PADDING, y + INSIDE_PADDING),
+                    (0.0, 0.0),
+                    (width, height),
+                    Some(Bounds {
+                        x,
+                        y,
+                        width: box_width,
+                        height: box_height,
+                    }),
+                )
+            }
+        };
+        for position in &mut positions {
+            position.0 += origin.0;
+            position.1 += origin.1;
+        }
+
+        // The count always comes last, after the entries it stands in for
+        let more = match more {
+            Some(more) if positions.len() > visible => {
+                positions.pop().map(|position| (more, position))
+            }
+            _ => None,
+        };
+
+        Self {
+            positions,
+            more,
+            backdrop,
+            chart,
+            size,
+        }
+    }
+
+    /// Draws `entries` where they were laid out. Draw the chart first, since a legend inside
+    /// it goes on top.
+    pub fn draw(&self, canvas: &dyn Canvas, entries: &[LegendEntry], theme: &Theme) {
+        if let Some(backdrop) = self.backdrop {
+            canvas.set_fill_style(&theme.background);
+            canvas.fill_rect(backdrop.x, backdrop.y, backdrop.width, backdrop.height);
+        }
+
+        canvas.set_font(&theme.font);
+        canvas.set_text_align("left");
+        canvas.set_text_baseline("middle");
+
+        for (i, (entry, &(x, y))) in entries.iter().zip(&self.positions).enumerate() {
+            let middle = y + ROW_HEIGHT / 2.0;
+
+            canvas.set_fill_style(&theme.text_color);
+            canvas.fill_text(&entry.label, x + entry.swatch.width() + LABEL_GAP, middle);
+
+            let swatch_x = x;
+            let swatch_y = middle - SWATCH_SIZE / 2.0;
+            canvas.set_fill_style(theme.color_or_palette(&entry.color, i));
+            match entry.swatch {
+                SwatchShape::Square => {
+                    canvas.fill_rect(swatch_x, swatch_y, SWATCH_SIZE, SWATCH_SIZE);
+                }
+                SwatchShape::Circle => {
+                    canvas.begin_path();
+                    canvas.arc(
+                        swatch_x + SWATCH_SIZE / 2.0,
+                        middle,
+                        SWATCH_SIZE / 2.0,
+                        0.0,
+                        2.0 * PI,
+                    );
+                    canvas.fill();
+                }
+                SwatchShape::Line => {
+                    canvas.fill_rect(swatch_x, middle - 1.0, LINE_SWATCH_WIDTH, 2.0);
+                    continue;
+                }
+            }
+
+            if let Some(pattern) = entry.pattern {
+                // Circles are still the current path
+                if entry.swatch == SwatchShape::Square {
+                    canvas.begin_path();
+                    canvas.rect(swatch_x, swatch_y, SWATCH_SIZE, SWATCH_SIZE);
+                }
+                canvas.fill_pattern(pattern, &theme.pattern_color);
+            }
+        }
+
+        if let Some((more, (x, y))) = &self.more {
+            canvas.set_fill_style(&theme.text_color);
+            canvas.fill_text(more, *x, y + ROW_HEIGHT / 2.0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::charts::recording::{DrawCommand, RecordingCanvas};
+
+    /// Lays out entries with `label_widths` for a chart of 200 by 100 pixels.
+    fn layout(config: &LegendConfig, label_widths: &[f64], width: f64) -> LegendLayout {
+        LegendLayout::new(
+            config,
+            label_widths,
+            |text| text.len() as f64 * 5.0,
+            (width, 100.0),
+            (10.0, 20.0, 30.0, 40.0),
+        )
+    }
+
+    #[test]
+    fn entries_flow_left_to_right() {
+        let layout = layout(&LegendConfig::default(), &[20.0, 30.0], 200.0);
+
+        assert_eq!(layout.positions, vec![(0.0, 0.0), (37.0, 0.0)]);
+        assert_eq!(layout.chart, (0.0, ROW_HEIGHT));
+        assert_eq!(layout.size, (200.0, 100.0 + ROW_HEIGHT));
+    }
+
+    #[test]
+    fn entries_wrap_onto_new_rows() {
+        let layout = layout(&LegendConfig::default(), &[50.0, 50.0, 50.0], 130.0);
+
+        assert_eq!(layout.positions, vec![(0.0, 0.0), (67.0, 0.0), (0.0, 14.0)]);
+        assert_eq!(layout.chart, (0.0, 2.0 * ROW_HEIGHT));
+    }
+
+    #[test]
+    fn wide_entries_still_get_a_row() {
+        let layout = layout(&LegendConfig::default(), &[500.0], 100.0);
+
+        assert_eq!(layout.positions, vec![(0.0, 0.0)]);
+    }
+
+    #[test]
+    fn empty_legend_takes_no_space() {
+        let layout = layout(&LegendConfig::default(), &[], 100.0);
+
+        assert_eq!(layout.chart, (0.0, 0.0));
+        assert_eq!(layout.size, (100.0, 100.0));
+    }
+
+    #[test]
+    fn line_swatches_are_wider() {
+        let config = LegendConfig::default().with_swatch(SwatchShape::Line);
+        let layout = layout(&config, &[20.0, 30.0], 200.0);
+
+        assert_eq!(layout.positions, vec![(0.0, 0.0), (41.0, 0.0)]);
+    }
+
+    #[test]
+    fn legends_below_and_beside_the_chart_make_room_for_themselves() {
+        let below = LegendConfig::default().with_position(LegendPosition::Bottom);
+        let below = layout(&below, &[20.0, 30.0], 200.0);
+        assert_eq!(below.positions, vec![(0.0, 100.0), (37.0, 100.0)]);
+        assert_eq!(below.chart, (0.0, 0.0));
+        assert_eq!(below.size, (200.0, 100.0 + ROW_HEIGHT));
+
+        // A column as wide as the widest entry, padded on both sides
+        let left = LegendConfig::default().with_position(LegendPosition::Left);
+        let left = layout(&left, &[20.0, 30.0], 200.0);
+        assert_eq!(left.positions, vec![(5.0, 0.0), (5.0, 14.0)]);
+        assert_eq!(left.chart, (52.0, 0.0));
+        assert_eq!(left.size, (252.0, 100.0));
+
+        let right = LegendConfig::default().with_position(LegendPosition::Right);
+        let right = layout(&right, &[20.0, 30.0], 200.0);
+        assert_eq!(right.positions, vec![(205.0, 0.0), (205.0, 14.0)]);
+        assert_eq!(right.chart, (0.0, 0.0));
+        assert_eq!(right.size, (252.0, 100.0));
+    }
+
+    #[test]
+    fn entries_are_aligned_along_the_legend() {
+        let centered = LegendConfig::default().with_align(LegendAlign::Center);
+        let centered = layout(&centered, &[20.0, 30.0], 200.0);
+        assert_eq!(centered.positions, vec![(60.5, 0.0), (97.5, 0.0)]);
+
+        let bottom = LegendConfig::default()
+            .with_position(LegendPosition::Right)
+            .with_align(LegendAlign::End);
+        let bottom = layout(&bottom, &[20.0, 30.0], 200.0);
+        assert_eq!(bottom.positions, vec![(205.0, 72.0), (205.0, 86.0)]);
+    }
+
+    #[test]
+    fn inside_legends_are_drawn_over_a_corner_of_the_plot() {
+        let config = LegendConfig::default()
+            .with_position(LegendPosition::Inside(Corner::BottomRight))
+            .with_align(LegendAlign::End);
+        let layout = layout(&config, &[20.0, 30.0], 200.0);
+
+        assert_eq!(
+            layout.backdrop,
+            Some(Bounds {
+                x: 130.0,
+                y: 34.0,
+                width: 50.0,
+                height: 36.0,
+            })
+        );
+        assert_eq!(layout.positions, vec![(144.0, 38.0), (134.0, 52.0)]);
+        assert_eq!(layout.chart, (0.0, 0.0));
+        assert_eq!(layout.size, (200.0, 100.0));
+    }
+
+    #[test]
+    fn exported_legends_keep_to_max_rows() {
+        let more = LegendConfig::default()
+            .with_position(LegendPosition::Left)
+            .with_max_rows(3, LegendOverflow::More);
+        let more = layout(&more, &[20.0; 5], 200.0);
+        assert_eq!(more.positions, vec![(5.0, 0.0), (5.0, 14.0)]);
+        assert_eq!(more.more, Some(("+3 more".to_string(), (5.0, 28.0))));
+
+        // What is on screen before scrolling, without a count
+        let scroll = LegendConfig::default().with_max_rows(1, LegendOverflow::Scroll);
+        let scroll = layout(&scroll, &[50.0; 6], 140.0);
+        assert_eq!(scroll.positions, vec![(0.0, 0.0), (67.0, 0.0)]);
+        assert_eq!(scroll.more, None);
+        assert_eq!(scroll.chart, (0.0, ROW_HEIGHT));
+    }
+
+    #[test]
+    fn swatches_come_before_their_labels() {
+        let canvas = RecordingCanvas::new();
+        let entries = [LegendEntry {
+            label: "Sales".to_string(),
+            color: "#ff0000".to_string(),
+            pattern: None,
+            swatch: SwatchShape::Square,
+        }];
+        layout(&LegendConfig::default(), &[20.0], 200.0).draw(&canvas, &entries, &Theme::light());
+
+        let commands = canvas.commands();
+        assert!(commands.contains(&DrawCommand::FillRect {
+            x: 0.0,
+            y: 2.0,
+            width: SWATCH_SIZE,
+            height: SWATCH_SIZE,
+        }));
+        assert!(commands.contains(&DrawCommand::FillText {
+            text: "Sales".to_string(),
+            x: SWATCH_SIZE + LABEL_GAP,
+            y: ROW_HEIGHT / 2.0,
+        }));
+    }
+
+    #[test]
+    fn entries_beyond_max_rows_are_counted_in_the_last_row() {
+        let beside = LegendConfig::default()
+            .with_position(LegendPosition::Right)
+            .with_max_rows(3, LegendOverflow::More);
+        assert_eq!(beside.visible_entries(&[20.0; 5], None), 2);
+        assert_eq!(beside.visible_entries(&[20.0; 3], None), 3);
+
+        // Two 67px entries fit across each row
+        let above = LegendConfig::default().with_max_rows(2, LegendOverflow::More);
+        assert_eq!(above.visible_entries(&[50.0; 6], Some(140.0)), 3);
+        assert_eq!(above.visible_entries(&[50.0; 4], Some(140.0)), 4);
+        // Until the legend has been measured, all entries are shown
+        assert_eq!(above.visible_entries(&[50.0; 6], None), 6);
+
+        // A single row still shows an entry next to the count
+        let one_row = LegendConfig::default().with_max_rows(1, LegendOverflow::More);
+        assert_eq!(one_row.visible_entries(&[50.0; 6], Some(140.0)), 1);
+        assert_eq!(one_row.visible_entries(&[200.0; 2], Some(140.0)), 1);
+        let one_beside = one_row.with_position(LegendPosition::Left);
+        assert_eq!(one_beside.visible_entries(&[20.0; 5], None), 1);
+        assert_eq!(one_beside.visible_entries(&[20.0], None), 1);
+
+        let scrolling = LegendConfig::default().with_max_rows(1, LegendOverflow::Scroll);
+        assert_eq!(scrolling.visible_entries(&[50.0; 6], Some(140.0)), 6);
+        assert!(scrolling
+            .style(&Theme::light(), (0.0, 0.0, 0.0, 0.0))
+            .contains("max-height: 14px; overflow-y: auto;"));
+    }
+
+    #[test]
+    fn inside_legends_sit_in_a_corner_of_the_plot() {
+        let config = LegendConfig::default()
+            .with_position(LegendPosition::Inside(Corner::BottomLeft))
+            .with_align(LegendAlign::End);
+        let style = config.style(&Theme::light(), (50.0, 40.0, 30.0, 20.0));
+
+        assert!(style.contains("position: absolute; bottom: 30px; left: 20px;"));
+        assert!(style.contains("align-items: flex-end;"));
+    }
+}
@@ -1 +1,2 @@
-pub mod line_chart;
\ No newline at end of file
+#[allow(clippy::module_inception)]
+pub mod line_chart;
@@ -1,4 +1,31 @@
+pub(crate) mod accessibility;
+pub mod animation;
+pub mod annotation;
 pub mod bar_chart;
-pub mod pie_chart;
+pub mod brush;
+pub mod canvas;
+pub mod crosshair;
+pub mod decimation;
+pub mod doughnut_chart;
+pub mod export;
+pub mod group;
+pub mod interaction;
+pub mod interval;
+pub mod legend;
 pub mod line_chart;
-pub mod doughnut_chart;
\ No newline at end of file
+pub mod pattern;
+pub mod pie_chart;
+#[cfg(feature = "Raster")]
+pub mod raster;
+#[cfg(test)]
+pub(crate) mod recording;
+pub(crate) mod resize;
+pub mod scene;
+pub mod sizing;
+pub mod streaming;
+pub mod svg;
+pub(crate) mod text;
+pub mod theme;
+pub mod title;
+pub mod trend;
+pub mod zoom;
@@ -0,0 +1,251 @@
+use web_sys::{wasm_bindgen::JsCast, window, CanvasRenderingContext2d, HtmlCanvasElement};
+
+use crate::charts::canvas::Canvas;
+
+/// A texture drawn over a fill, so that bars, slices and areas can be told apart without
+/// relying on color alone, e.g. by colorblind users or on grayscale prints.
+///
+/// Patterns are drawn in the theme's [`pattern_color`](crate::theme::Theme::pattern_color) on
+/// top of the regular fill color.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Pattern {
+    DiagonalHatch,
+    Dots,
+    CrossHatch,
+    ZigZag,
+}
+
+/// Patterns are drawn on a square tile of this size, in CSS pixels, repeated across the fill.
+pub(crate) const TILE_SIZE: f64 = 8.0;
+
+/// A single shape on a pattern tile.
+#[derive(Debug, PartialEq)]
+enum Mark {
+    Line { points: Vec<(f64, f64)>, width: f64 },
+    Dot { x: f64, y: f64, radius: f64 },
+}
+
+impl Pattern {
+    /// The shapes making up one tile. Lines crossing the tile's edges are continued on the
+    /// opposite edge so that the tiles join up seamlessly.
+    fn marks(&self) -> Vec<Mark> {
+        let line = |points: &[(f64, f64)]| Mark::Line {
+            points: points.to_vec(),
+            width: 1.5,
+        };
+        let rising = || {
+            vec![
+                line(&[(0.0, 8.0), (8.0, 0.0)]),
+                line(&[(-2.0, 2.0), (2.0, -2.0)]),
+                line(&[(6.0, 10.0), (10.0, 6.0)]),
+            ]
+        };
+
+        match self {
+            Pattern::DiagonalHatch => rising(),
+            Pattern::CrossHatch => {
+                let mut marks = rising();
+                marks.extend([
+                    line(&[(0.0, 0.0), (8.0, 8.0)]),
+                    line(&[(-2.0, 6.0), (2.0, 10.0)]),
+                    line(&[(6.0, -2.0), (10.0, 2.0)]),
+                ]);
+                marks
+            }
+            Pattern::Dots => vec![
+                Mark::Dot {
+                    x: 2.0,
+                    y: 2.0,
+                    radius: 1.25,
+                },
+                Mark::Dot {
+                    x: 6.0,
+                    y: 6.0,
+                    radius: 1.25,
+                },
+            ],
+            Pattern::ZigZag => vec![Mark::Line {
+                points: vec![(0.0, 6.0), (2.0, 2.0), (4.0, 6.0), (6.0, 2.0), (8.0, 6.0)],
+                width: 1.0,
+            }],
+        }
+    }
+
+    /// The pattern's shapes as SVG elements, for a tile at the origin.
+    pub(crate) fn svg_marks(&self, ink: &str) -> String {
+        self.marks()
+            .into_iter()
+            .map(|mark| match mark {
+                Mark::Line { points, width } => {
+                    let path = points
+                        .iter()
+                        .enumerate()
+                        .map(|(i, (x, y))| format!("{}{} {}", if i == 0 { "M" } else { "L" }, x, y))
+                        .collect::<String>();
+                    format!(
+                        "<path d='{}' fill='none' stroke='{}' stroke-width='{}'/>",
+                        path, ink, width
+                    )
+                }
+                Mark::Dot { x, y, radius } => {
+                    format!(
+                        "<circle cx='{}' cy='{}' r='{}' fill='{}'/>",
+                        x, y, radius, ink
+                    )
+                }
+            })
+            .collect()
+    }
+
+    /// One tile of the pattern as an SVG image.
+    fn svg_tile(&self, ink: &str) -> String {
+        format!(
+            "<svg xmlns='http://www.w3.org/2000/svg' width='{size}' height='{size}'>{}</svg>",
+            self.svg_marks(ink),
+            size = TILE_SIZE
+        )
+    }
+
+    /// Draws one tile of the pattern at the origin of `canvas`.
+    pub(crate) fn draw_tile(&self, canvas: &dyn Canvas, ink: &str) {
+        canvas.set_stroke_style(ink);
+        canvas.set_fill_style(ink);
+
+        for mark in self.marks() {
+            canvas.begin_path();
+            match mark {
+                Mark::Line { points, width } => {
+                    canvas.set_line_width(width);
+                    for (i, &(x, y)) in points.iter().enumerate() {
+                        if i == 0 {
+                            canvas.move_to(x, y);
+                        } else {
+                            canvas.line_to(x, y);
+                        }
+                    }
+                    canvas.stroke();
+                }
+                Mark::Dot { x, y, radius } => {
+                    canvas.arc(x, y, radius, 0.0, std::f64::consts::PI * 2.0);
+                    canvas.fill();
+                }
+            }
+        }
+    }
+
+    /// Draws one tile of the pattern onto a new canvas, at the device's pixel density.
+    fn canvas_tile(&self, ink: &str, pixel_ratio: f64) -> Option<HtmlCanvasElement> {
+        let tile = window()?
+            .document()?
+            .create_element("canvas")
+            .ok()?
+            .dyn_into::<HtmlCanvasElement>()
+            .ok()?;
+        let size = (TILE_SIZE * pixel_ratio).round() as u32;
+        tile.set_width(size);
+        tile.set_height(size);
+
+        let context = tile
+            .get_context("2d")
+            .ok()??
+            .dyn_into::<CanvasRenderingContext2d>()
+            .ok()?;
+        let scale = size as f64 / TILE_SIZE;
+        let _ignored_result = context.scale(scale, scale);
+        context.set_line_cap("square");
+        self.draw_tile(&context, ink);
+
+        Some(tile)
+    }
+}
+
+/// Fills the current path of `context` with `pattern`, on top of whatever was filled before.
+pub(crate) fn fill_pattern(context: &CanvasRenderingContext2d, pattern: Pattern, ink: &str) {
+    let pixel_ratio = window().map_or(1.0, |window| window.device_pixel_ratio());
+    let Some(fill) = pattern.canvas_tile(ink, pixel_ratio).and_then(|tile| {
+        context
+            .create_pattern_with_html_canvas_element(&tile, "repeat")
+            .ok()
+            .flatten()
+    }) else {
+        return;
+    };
+
+    // The path is already in device pixels, while the pattern follows the transform at the time
+    // it is filled. Filling without the pixel ratio scale keeps the tile sharp.
+    context.save();
+    let _ignored_result = context.set_transform(1.0, 0.0, 0.0, 1.0, 0.0, 0.0);
+    context.set_fill_style(&fill);
+    context.fill();
+    context.restore();
+}
+
+/// The CSS `background` of an HTML swatch, such as a legend entry, showing `color` with
+/// `pattern` drawn over it.
+pub(crate) fn swatch_background(color: &str, pattern: Option<Pattern>, ink: &str) -> String {
+    match pattern {
+        Some(pattern) => {
+            let svg = pattern
+                .svg_tile(ink)
+                .replace('%', "%25")
+                .replace('#', "%23")
+                .replace('<', "%3C")
+                .replace('>', "%3E")
+                .replace('"', "'");
+            format!(
+                "background: url(\"data:image/svg+xml,{}\") 0 0 / {}px {}px, {};",
+                svg, TILE_SIZE, TILE_SIZE, color
+            )
+        }
+        None => format!("background-color: {};", color),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PATTERNS: [Pattern; 4] = [
+        Pattern::DiagonalHatch,
+        Pattern::Dots,
+        Pattern::CrossHatch,
+        Pattern::ZigZag,
+    ];
+
+    #[test]
+    fn every_pattern_has_marks() {
+        for pattern in PATTERNS {
+            assert!(!pattern.marks().is_empty(), "{:?}", pattern);
+        }
+        assert_eq!(
+            Pattern::CrossHatch.marks().len(),
+            Pattern::DiagonalHatch.marks().len() * 2
+        );
+    }
+
+    #[test]
+    fn svg_tile_draws_marks_in_ink() {
+        assert_eq!(
+            Pattern::Dots.svg_tile("red"),
+            "<svg xmlns='http://www.w3.org/2000/svg' width='8' height='8'>\
+             <circle cx='2' cy='2' r='1.25' fill='red'/>\
+             <circle cx='6' cy='6' r='1.25' fill='red'/></svg>"
+        );
+        assert!(Pattern::ZigZag.svg_tile("red").contains(
+            "<path d='M0 6L2 2L4 6L6 2L8 6' fill='none' stroke='red' stroke-width='1'/>"
+        ));
+    }
+
+    #[test]
+    fn swatch_layers_pattern_over_color() {
+        assert_eq!(
+            swatch_background("#36a2eb", None, "black"),
+            "background-color: #36a2eb;"
+        );
+
+        let background = swatch_background("#36a2eb", Some(Pattern::DiagonalHatch), "#000");
+        assert!(background.starts_with("background: url(\"data:image/svg+xml,%3Csvg "));
+        assert!(background.contains("stroke='%23000'"));
+        assert!(background.ends_with(") 0 0 / 8px 8px, #36a2eb;"));
+    }
+}
@@ -1 +1,2 @@
-pub mod pie_chart;
\ No newline at end of file
+#[allow(clippy::module_inception)]
+pub mod pie_chart;
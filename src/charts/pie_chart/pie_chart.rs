@@ -1,23 +1,54 @@
-use gloo::events::EventListener;
-use web_sys::{
-    wasm_bindgen::{JsCast, JsValue},
-    window, CanvasRenderingContext2d, HtmlCanvasElement,
-};
+use std::cell::RefCell;
+use std::rc::Rc;
+use web_sys::{wasm_bindgen::JsCast, CanvasRenderingContext2d, HtmlCanvasElement};
 use yew::prelude::*;
 
+use crate::charts::accessibility::ChartDescription;
+use crate::charts::animation::{animate, AnimationConfig, AnimationFrame};
+use crate::charts::canvas::{Canvas, Chart};
+use crate::charts::export::{ChartExport, ExportSource};
+use crate::charts::interaction::{
+    canvas_offset, clear_focus_on_blur, keyboard_navigation, live_region, tooltip,
+    use_canvas_state, PointIndex,
+};
+use crate::charts::legend::{
+    with_legend, Legend, LegendConfig, LegendContext, LegendEntry, LegendItem,
+};
+use crate::charts::pattern::Pattern;
+use crate::charts::resize::{listen_for_resize, prepare_canvas};
+use crate::charts::scene::{PatternFill, Scene, SceneItem, Sector, Stroke};
+use crate::charts::sizing::{fit_canvas, ChartSize};
+use crate::charts::theme::{use_theme, Theme};
+use crate::charts::title::ChartTitles;
+
 #[derive(Clone, Debug, PartialEq, Eq, Properties, Default)]
 pub struct PieChartConfig {
     #[prop_or("center".to_string())]
     pub text_align: String,
     #[prop_or(true)]
     pub show_legend: bool,
+    /// Defaults to the container's width with a 5:4 aspect ratio.
+    #[prop_or_default]
+    pub size: ChartSize,
+    /// Slices sweep in from the start angle when the chart appears and resize smoothly on updates.
+    #[prop_or_default]
+    pub animation: AnimationConfig,
+    /// A title and subtitle above the chart and a caption below it.
+    #[prop_or_default]
+    pub titles: ChartTitles,
+    /// Where the legend goes and how its entries are drawn.
+    #[prop_or_default]
+    pub legend: LegendConfig,
 }
 
+/// Leave `color` empty to pick the next color from the active [`Theme`]'s palette.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct DataPoint {
     pub name: String,
     pub value: i32,
     pub color: String,
+    /// Drawn over the slice's color and its legend swatch, see [`Pattern`].
+    pub pattern: Option<Pattern>,
 }
 
 impl DataPoint {
@@ -26,228 +57,505 @@ impl DataPoint {
             name: name.into(),
             value,
             color: color.into(),
+            pattern: None,
         }
     }
+
+    pub fn with_pattern(mut self, pattern: Pattern) -> Self {
+        self.pattern = Some(pattern);
+        self
+    }
 }
 
-#[derive(Clone, Properties, PartialEq, Debug, Eq)]
+#[derive(Clone, Properties, PartialEq, Debug, Default)]
 pub struct PieChartProps {
     pub data: Vec<DataPoint>,
     #[prop_or(Default::default())]
     pub config: PieChartConfig,
+    /// Overrides the theme provided through [`crate::theme::ThemeProvider`].
+    #[prop_or_default]
+    pub theme: Option<Theme>,
+    /// Called with the focused slice when it is activated with Enter or Space.
+    #[prop_or_default]
+    pub on_point_click: Option<Callback<PointIndex>>,
+    /// Lets the chart be exported as an image, see [`ChartExport`].
+    #[prop_or_default]
+    pub export: Option<ChartExport>,
+    /// Formats each legend entry's label, e.g. as `"A — 25%"` from its [`LegendItem::share`].
+    #[prop_or_default]
+    pub legend_label: Option<Callback<LegendItem, String>>,
+    /// Draws a legend of its own in place of the built-in one.
+    #[prop_or_default]
+    pub render_legend: Option<Callback<LegendContext, Html>>,
 }
 
 /// This is how you can create a PieChart component configuration:
 ///```
+/// # use visualize_yew::charts::pie_chart::pie_chart::{DataPoint, PieChartConfig, PieChartProps};
+/// # use visualize_yew::pattern::Pattern;
 /// let props = PieChartProps {
 ///     data: vec![
 ///         DataPoint::new("A", 10, ""),
-///         DataPoint::new("B", 20, ""),
+///         DataPoint::new("B", 20, "").with_pattern(Pattern::Dots),
 ///         DataPoint::new("C", 30, ""),
 ///         DataPoint::new("D", 40, ""),
 ///     ],
 ///     config: PieChartConfig::default(),
+///     ..Default::default()
 /// };
 /// ```
-/// #[cfg(feature = "PieChart")]
 #[function_component]
 pub fn PieChart(props: &PieChartProps) -> Html {
     let canvas_ref = use_node_ref();
+    let theme = use_theme(props.theme.clone());
+    // The values currently on screen, so that updates animate from where the chart is
+    let displayed = use_mut_ref(|| None::<Vec<Vec<f64>>>);
+    let state = use_canvas_state();
+    let focus = use_state(|| None::<PointIndex>);
 
     {
         let canvas_ref = canvas_ref.clone();
-        let props_clone = props.clone();
-        use_effect_with((), move |_| {
-            let canvas = canvas_ref
-                .cast::<HtmlCanvasElement>()
-                .expect("Failed to get canvas element");
-
-            let context = canvas
-                .get_context("2d")
-                .unwrap()
-                .unwrap()
-                .dyn_into::<CanvasRenderingContext2d>()
-                .unwrap();
-
-            let props_clone_resize = props_clone.clone();
-            let resize_callback = {
-                let canvas_ref = canvas_ref.clone();
-                move || {
-                    let canvas = canvas_ref
-                        .cast::<HtmlCanvasElement>()
-                        .expect("Failed to get canvas element");
-
-                    let device_pixel_ratio = window().unwrap().device_pixel_ratio();
-                    let parent = canvas.parent_element().unwrap();
-                    let width = parent.client_width() as f64;
-                    // let height = parent.client_height() as f64;
-                    let height = width * 0.8;
-
-                    // Set the canvas dimensions to match its parent's dimensions
-                    canvas.set_width((width * device_pixel_ratio) as u32);
-                    canvas.set_height((height * device_pixel_ratio) as u32);
-
-                    // Scale the context to account for the device pixel ratio
-                    context
-                        .scale(device_pixel_ratio, device_pixel_ratio)
-                        .unwrap();
-
-                    draw_pie_chart(&context, width, height, &props_clone_resize);
+        let state = state.clone();
+        use_effect_with(
+            (props.clone(), theme.clone()),
+            move |(props_clone, theme)| {
+                let canvas = canvas_ref
+                    .cast::<HtmlCanvasElement>()
+                    .expect("Failed to get canvas element");
+
+                let context = canvas
+                    .get_context("2d")
+                    .unwrap()
+                    .unwrap()
+                    .dyn_into::<CanvasRenderingContext2d>()
+                    .unwrap();
+
+                let targets = vec![props_clone
+                    .data
+                    .iter()
+                    .map(|data_point| data_point.value as f64)
+                    .collect::<Vec<f64>>()];
+                let frame = Rc::new(RefCell::new(AnimationFrame::start(
+                    displayed.borrow().clone(),
+                )));
+
+                // Let the export handle draw the finished chart again at any size
+                let export = props_clone.export.clone();
+                if let Some(export) = &export {
+                    export.set_source(ExportSource {
+                        canvas: canvas_ref.clone(),
+                        theme: theme.clone(),
+                        chart: Rc::new(props_clone.clone()),
+                    });
                 }
-            };
-
-            resize_callback(); // Initial call to set canvas size
 
-            let listener = EventListener::new(&window().unwrap(), "resize", move |_event| {
-                resize_callback();
-            });
+                let props_clone_resize = props_clone.clone();
+                let theme = theme.clone();
+                let resize_callback: Rc<dyn Fn()> = {
+                    let canvas_ref = canvas_ref.clone();
+                    let frame = frame.clone();
+                    let state = state.clone();
+                    Rc::new(move || {
+                        let canvas = canvas_ref
+                            .cast::<HtmlCanvasElement>()
+                            .expect("Failed to get canvas element");
+
+                        let parent = canvas.parent_element().unwrap();
+                        let (width, height) =
+                            fit_canvas(&canvas, &props_clone_resize.config.size, 0.8);
+
+                        // Match the backing store to the device pixel ratio and draw in CSS pixels
+                        prepare_canvas(&canvas, &context, width, height);
+
+                        // Resolve CSS custom properties against the container
+                        let theme = theme.resolve_css_variables(&parent);
+
+                        state.set_size(width, height);
+
+                        let frame = frame.borrow();
+                        draw_pie_chart(
+                            &context,
+                            width,
+                            height,
+                            &props_clone_resize,
+                            &theme,
+                            &frame,
+                            state.focus(),
+                        );
+
+                        // Remember what is on screen so the next update animates from here
+                        *displayed.borrow_mut() = Some(frame.interpolate_all(&targets, Some(0.0)));
+                    })
+                };
+
+                state.set_redraw(resize_callback.clone());
+
+                // Draws the first frame right away, then one per animation frame until done
+                let animation = animate(
+                    &props_clone.config.animation,
+                    frame,
+                    resize_callback.clone(),
+                );
+
+                let listener = listen_for_resize(&canvas, move || resize_callback());
 
-            move || drop(listener) // Clean up the event listener on component unmount
-        });
+                move || {
+                    // Stop animating and observing the container on component unmount
+                    drop(animation);
+                    drop(listener);
+                    if let Some(export) = &export {
+                        export.clear();
+                    }
+                }
+            },
+        );
     }
 
     let legend_html = if props.config.show_legend {
         html! {
-            <div style="display: flex; flex-direction: row; gap: 5px; flex-wrap: wrap;">
-                { for props.data.iter().map(|data_point| {
-                    html! {
-                        <div style="display: flex; flex-direction: row; align-items: center; gap: 2px;">
-                                <span style="font-size: 10px;">{ &data_point.name }</span>
-                            <div style={format!("background-color: {}; width: 10px; height: 10px; display: inline-block;", &data_point.color)}></div>
-                        </div>
-                    }
-                })}
-            </div>
+            <Legend
+                config={props.config.legend.clone()}
+                items={props.legend_items(&theme)}
+                theme={theme.clone()}
+                label={props.legend_label.clone()}
+                render={props.render_legend.clone()}
+                inset={props.plot_inset(&theme)}
+            />
         }
     } else {
         html! {}
     };
+    let description = ChartDescription::new(
+        "Pie chart",
+        props
+            .data
+            .iter()
+            .map(|data_point| data_point.name.clone())
+            .collect(),
+    )
+    .with_series(
+        "Value",
+        props
+            .data
+            .iter()
+            .map(|data_point| data_point.value as f64)
+            .collect(),
+    )
+    .with_titles(&props.config.titles);
+
+    let onkeydown = keyboard_navigation(
+        &state,
+        &focus,
+        vec![props.data.len()],
+        props.on_point_click.clone(),
+    );
+    let onblur = clear_focus_on_blur(&state, &focus);
+
+    let focused_point = (*focus).and_then(|point| {
+        let data_point = props.data.get(point.index)?;
+        let (width, height) = state.size();
+        let (x, y) = props.scene(width, height, &theme).anchor(point)?;
+        let (offset_x, offset_y) = canvas_offset(&canvas_ref);
+        let text = format!("{}: {}", data_point.name, data_point.value);
+
+        Some((tooltip(&theme, x + offset_x, y + offset_y, &text), text))
+    });
+    let (tooltip_html, announcement) = focused_point.unwrap_or_default();
+
+    let canvas_html = html! {
+        <canvas
+            ref={canvas_ref}
+            role="img"
+            aria-label={description.aria_label()}
+            style="display: block;"
+        ></canvas>
+    };
 
     html! {
-        <div>
-            // legend
-            { legend_html }
-            <canvas ref={canvas_ref} style="width: 100%; height: 100%;"></canvas>
+        <div
+            style={format!("position: relative; {}", props.config.size.container_style())}
+            tabindex="0"
+            {onkeydown}
+            {onblur}
+        >
+            { with_legend(props.config.legend.position, legend_html, canvas_html) }
+            { tooltip_html }
+            { live_region(&announcement) }
+            { description.table() }
         </div>
     }
 }
 
-pub fn draw_pie_chart(
-    context: &CanvasRenderingContext2d,
+fn radius(width: f64, height: f64) -> f64 {
+    (width.min(height) / 2.0) - 5.0
+}
+
+/// Lays out a slice per data point, clockwise from the positive x-axis.
+fn pie_chart_scene(
     width: f64,
     height: f64,
     props: &PieChartProps,
-) {
-    let data = props
-        .data
-        .iter()
-        .map(|data_point| data_point.value)
-        .collect::<Vec<i32>>();
-    // let labels = props.data.iter().map(|data_point| data_point.name.clone()).collect::<Vec<String>>();
-    // randomize color selection(use a cool color palette)
-    let colors = props
-        .data
-        .iter()
-        .map(|data_point| data_point.color.clone())
-        .collect::<Vec<String>>();
-
-    // Calculate the total sum of the data
-    let total: f64 = data.iter().sum::<i32>() as f64;
-
-    // Set up initial angle
+    theme: &Theme,
+    frame: &AnimationFrame,
+    focused: Option<PointIndex>,
+) -> Scene {
+    // The chart is laid out in the space left between its titles and caption
+    let height = props.config.titles.inner_height(height, theme);
+    // Slices sweep in from the start angle on entry and change size on updates
+    let (data, sweep) = if frame.is_entering() {
+        let data = props
+            .data
+            .iter()
+            .map(|data_point| data_point.value as f64)
+            .collect::<Vec<f64>>();
+        (data, frame.progress())
+    } else {
+        let data = props
+            .data
+            .iter()
+            .enumerate()
+            .map(|(i, data_point)| frame.interpolate(0, i, 0.0, data_point.value as f64))
+            .collect::<Vec<f64>>();
+        (data, 1.0)
+    };
+
+    let total: f64 = data.iter().sum();
     let mut start_angle = 0.0;
+    let mut items = Vec::new();
+
+    for (i, (&value, data_point)) in data.iter().zip(&props.data).enumerate() {
+        let slice_angle = value / total * std::f64::consts::PI * 2.0 * sweep;
+
+        items.push(SceneItem::Sector(Sector {
+            center: (width / 2.0, height / 2.0),
+            radius: radius(width, height),
+            inner_radius: 0.0,
+            start_angle,
+            end_angle: start_angle + slice_angle,
+            fill: theme.color_or_palette(&data_point.color, i).to_string(),
+            pattern: data_point.pattern.map(|pattern| PatternFill {
+                pattern,
+                ink: theme.pattern_color.clone(),
+            }),
+            // Outline the slice focused with the keyboard
+            outline: focused
+                .is_some_and(|point| point.index == i)
+                .then(|| Stroke {
+                    color: theme.text_color.clone(),
+                    width: 2.0,
+                }),
+            point: Some(PointIndex::new(0, i)),
+        }));
 
-    for (i, &value) in data.iter().enumerate() {
-        // Calculate the slice angle
-        let slice_angle = value as f64 / total * std::f64::consts::PI * 2.0;
-
-        // Draw the slice
-        context.begin_path();
-        context.move_to(width / 2.0, height / 2.0);
-        context
-            .arc(
-                width / 2.0,
-                height / 2.0,
-                (width.min(height) / 2.0) - 5.0,
-                start_angle,
-                start_angle + slice_angle,
-            )
-            .unwrap();
-        context.close_path();
-
-        // Fill the slice with color
-        context.set_fill_style(&JsValue::from_str(colors[i].as_str()));
-        context.fill();
-
-        // Update the starting angle
         start_angle += slice_angle;
     }
 
-    // Draw labels
-    // context.set_fill_style(&JsValue::from_str("black"));
-    // context.set_text_align("center");
-    // context.set_text_baseline("middle");
+    props.config.titles.frame(
+        Scene {
+            width,
+            height,
+            background: theme.background.clone(),
+            items,
+        },
+        theme,
+    )
+}
 
-    // start_angle = 0.0;
-    // for (i, &value) in data.iter().enumerate() {
-    //     let slice_angle = value as f64 / total * std::f64::consts::PI * 2.0;
-    //     let label_angle = start_angle + slice_angle / 2.0;
+pub fn draw_pie_chart(
+    context: &dyn Canvas,
+    width: f64,
+    height: f64,
+    props: &PieChartProps,
+    theme: &Theme,
+    frame: &AnimationFrame,
+    focused: Option<PointIndex>,
+) {
+    pie_chart_scene(width, height, props, theme, frame, focused).paint(context);
+}
+
+impl PieChartProps {
+    /// The legend's entries, with each slice's share of the whole.
+    fn legend_items(&self, theme: &Theme) -> Vec<LegendItem> {
+        let total: f64 = self
+            .data
+            .iter()
+            .map(|data_point| data_point.value as f64)
+            .sum();
+        self.data
+            .iter()
+            .enumerate()
+            .map(|(i, data_point)| LegendItem {
+                index: i,
+                name: data_point.name.clone(),
+                color: theme.color_or_palette(&data_point.color, i).to_string(),
+                pattern: data_point.pattern,
+                value: Some(data_point.value as f64),
+                share: (total > 0.0).then(|| data_point.value as f64 / total),
+                hidden: false,
+            })
+            .collect()
+    }
+}
 
-    //     let label_x = width / 2.0 + (width.min(height) / 2.0 - 30.0) * label_angle.cos();
-    //     let label_y = height / 2.0 + (height.min(width) / 2.0 - 30.0) * label_angle.sin();
+impl Chart for PieChartProps {
+    fn scene(&self, width: f64, height: f64, theme: &Theme) -> Scene {
+        pie_chart_scene(
+            width,
+            height,
+            self,
+            theme,
+            &AnimationFrame::complete(),
+            None,
+        )
+    }
+
+    fn legend(&self) -> Vec<LegendEntry> {
+        if !self.config.show_legend {
+            return Vec::new();
+        }
 
-    //     context.fill_text(labels[i].as_str(), label_x, label_y).unwrap();
+        // Exports resolve palette colors against their own theme, so only the labels use this
+        let theme = self.theme.clone().unwrap_or_default();
+        self.legend_items(&theme)
+            .into_iter()
+            .zip(&self.data)
+            .map(|(item, data_point)| LegendEntry {
+                label: item.label(self.legend_label.as_ref()),
+                color: data_point.color.clone(),
+                pattern: item.pattern,
+                swatch: self.config.legend.swatch,
+            })
+            .collect()
+    }
+
+    fn legend_config(&self) -> LegendConfig {
+        self.config.legend.clone()
+    }
 
-    //     start_angle += slice_angle;
-    // }
+    fn plot_inset(&self, theme: &Theme) -> (f64, f64, f64, f64) {
+        let (top, bottom) = self.config.titles.space(theme);
+        (top, 0.0, bottom, 0.0)
+    }
+
+    fn theme(&self) -> Option<&Theme> {
+        self.theme.as_ref()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use wasm_bindgen_test::*;
-
-    wasm_bindgen_test_configure!(run_in_browser);
-
-    // Function to create a mock CanvasRenderingContext2d
-    fn mock_context() -> CanvasRenderingContext2d {
-        // Create a canvas element
-        let document = web_sys::window().unwrap().document().unwrap();
-        let canvas = document
-            .create_element("canvas")
-            .unwrap()
-            .dyn_into::<web_sys::HtmlCanvasElement>()
-            .unwrap();
-
-        // Get the 2D context from the canvas
-        canvas
-            .get_context("2d")
-            .unwrap()
-            .unwrap()
-            .dyn_into::<CanvasRenderingContext2d>()
-            .unwrap()
-    }
+    use crate::charts::legend::SwatchShape;
+    use crate::charts::recording::{assert_snapshot, DrawCommand, RecordingCanvas};
+    use std::f64::consts::PI;
 
-    #[wasm_bindgen_test]
+    #[test]
     fn test_draw_pie_chart() {
-        let context = mock_context();
+        let canvas = RecordingCanvas::new();
         let width = 800.0;
         let height = 600.0;
         let props = PieChartProps {
             data: vec![
                 DataPoint::new("A", 10, ""),
-                DataPoint::new("B", 20, ""),
+                DataPoint::new("B", 20, "").with_pattern(Pattern::ZigZag),
                 DataPoint::new("C", 30, ""),
                 DataPoint::new("D", 40, ""),
             ],
             config: PieChartConfig::default(),
+            ..Default::default()
         };
 
-        draw_pie_chart(&context, width, height, &props);
+        draw_pie_chart(
+            &canvas,
+            width,
+            height,
+            &props,
+            &Theme::light(),
+            &AnimationFrame::complete(),
+            Some(PointIndex::new(0, 2)),
+        );
+
+        let commands = canvas.commands();
+        let arcs = commands
+            .iter()
+            .filter_map(|command| match *command {
+                DrawCommand::Arc {
+                    x,
+                    y,
+                    radius,
+                    start_angle,
+                    end_angle,
+                    anticlockwise: false,
+                } => Some((x, y, radius, start_angle, end_angle)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        // One slice per value around the centre, each starting where the last one ended and
+        // together making up the whole circle
+        assert_eq!(arcs.len(), 4);
+        assert_eq!(arcs[0].3, 0.0);
+        for (arc, value) in arcs.iter().zip([10.0, 20.0, 30.0, 40.0]) {
+            assert_eq!((arc.0, arc.1, arc.2), (400.0, 300.0, 295.0));
+            assert!((arc.4 - arc.3 - value / 100.0 * 2.0 * PI).abs() < 1e-9);
+        }
+        assert!(arcs.windows(2).all(|pair| pair[0].4 == pair[1].3));
+        assert!((arcs[3].4 - 2.0 * PI).abs() < 1e-9);
+
+        // Only the focused slice is outlined, and only the patterned one gets a pattern
+        assert_eq!(
+            commands
+                .iter()
+                .filter(|command| **command == DrawCommand::Stroke)
+                .count(),
+            1
+        );
+        assert_eq!(
+            commands
+                .iter()
+                .filter(|command| matches!(command, DrawCommand::FillPattern { .. }))
+                .collect::<Vec<_>>(),
+            vec![&DrawCommand::FillPattern {
+                pattern: Pattern::ZigZag,
+                ink: Theme::light().pattern_color,
+            }]
+        );
+
+        assert_snapshot("pie_chart", &canvas.to_snapshot());
+    }
+
+    #[test]
+    fn legend_labels_are_formatted_from_each_slices_share() {
+        let props = PieChartProps {
+            data: vec![
+                DataPoint::new("A", 25, ""),
+                DataPoint::new("B", 75, "#00ff00"),
+            ],
+            config: PieChartConfig {
+                show_legend: true,
+                legend: LegendConfig::default().with_swatch(SwatchShape::Circle),
+                ..Default::default()
+            },
+            legend_label: Some(Callback::from(|item: LegendItem| {
+                format!("{} — {:.0}%", item.name, item.share.unwrap_or(0.0) * 100.0)
+            })),
+            ..Default::default()
+        };
 
-        // Additional assertions would be needed to validate the correct behavior,
-        // e.g., checking if certain methods were called or if certain values were set.
-        // Since we cannot directly inspect the canvas from here, we assume success if no panic occurs.
-        assert!(true);
+        let items = props.legend_items(&Theme::light());
+        assert_eq!(items[0].color, Theme::light().palette_color(0));
+        assert_eq!(items[1].value, Some(75.0));
+
+        let legend = props.legend();
+        assert_eq!(
+            legend
+                .iter()
+                .map(|entry| (entry.label.as_str(), entry.color.as_str(), entry.swatch))
+                .collect::<Vec<_>>(),
+            vec![
+                ("A — 25%", "", SwatchShape::Circle),
+                ("B — 75%", "#00ff00", SwatchShape::Circle),
+            ]
+        );
     }
 }
@@ -0,0 +1,995 @@
+use std::cell::RefCell;
+use std::fmt;
+use std::path::Path;
+use std::rc::Rc;
+
+use ab_glyph::{Font, FontArc, OutlineCurve};
+use tiny_skia::{
+    BlendMode, Color, FillRule, FilterQuality, Mask, Paint, PathBuilder, Pixmap, Rect, Shader,
+    SpreadMode, Stroke, StrokeDash,
+};
+
+use crate::charts::canvas::{arc_curves, arc_sweep, Canvas, Chart, Transform};
+use crate::charts::legend::LegendLayout;
+use crate::charts::pattern::{Pattern, TILE_SIZE};
+use crate::charts::text::font_size;
+
+/// Options for rendering a chart to a raster image with [`render`].
+///
+///```
+/// # use visualize_yew::raster::RasterOptions;
+/// let options = RasterOptions {
+///     pixel_ratio: 2.0,
+///     background: "#fafafa".into(),
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Clone, Debug)]
+pub struct RasterOptions {
+    /// Image pixels per CSS pixel, e.g. `2.0` for an image that stays sharp in print.
+    pub pixel_ratio: f64,
+    /// Painted behind the whole image, including the legend.
+    pub background: String,
+    /// The font all text is drawn in. Defaults to [`system_font`]. Without a font, labels and
+    /// the legend's text are left out.
+    ///
+    /// Font families and weights in the theme are ignored, so bold titles are drawn in this
+    /// font too.
+    pub font: Option<FontArc>,
+}
+
+impl Default for RasterOptions {
+    fn default() -> Self {
+        Self {
+            pixel_ratio: 1.0,
+            background: "white".into(),
+            font: None,
+        }
+    }
+}
+
+/// Why a chart couldn't be rendered or saved.
+#[derive(Debug)]
+pub enum RasterError {
+    /// The image would have no pixels, e.g. because the width is zero.
+    EmptyImage,
+    /// The image couldn't be encoded or written as a PNG file.
+    Png(String),
+}
+
+impl fmt::Display for RasterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RasterError::EmptyImage => write!(f, "the image has no pixels"),
+            RasterError::Png(error) => write!(f, "failed to write PNG: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for RasterError {}
+
+/// Renders `chart` to an image on the CPU, with its legend placed as the chart's legend config
+/// says.
+///
+/// This draws the chart with the same code as its component does on screen, so it works on a
+/// server without a browser, e.g. for PDF or email reports. The chart area is `width` by
+/// `height` CSS pixels and the image grows to fit a legend above, below or beside the chart. The chart is drawn with
+/// its `theme` prop, or [`Theme::light`] when it doesn't set one. Theme colors must be plain
+/// CSS colors, since there is no page to resolve CSS variables against.
+///
+///```
+/// # use visualize_yew::charts::bar_chart::bar_chart::{BarChartProps, DataPoint};
+/// # use visualize_yew::raster::{render, RasterOptions};
+/// let props = BarChartProps {
+///     data: vec![DataPoint::new("A", 10), DataPoint::new("B", 20)],
+///     config: Default::default(),
+///     ..Default::default()
+/// };
+///
+/// let image = render(&props, 400.0, 300.0, &RasterOptions::default()).unwrap();
+/// assert_eq!((image.width(), image.height()), (400, 300));
+/// let png = image.to_png().unwrap();
+/// ```
+///
+/// [`Theme::light`]: crate::theme::Theme::light
+pub fn render(
+    chart: &dyn Chart,
+    width: f64,
+    height: f64,
+    options: &RasterOptions,
+) -> Result<RasterImage, RasterError> {
+    let theme = chart.theme().cloned().unwrap_or_default();
+    let font = options.font.clone().or_else(system_font);
+
+    // Only the font is needed to lay the legend out, so measure it on a single pixel
+    let legend = chart.legend();
+    let mut measuring_canvas = RasterCanvas::new(1.0, 1.0, 1.0)?;
+    if let Some(font) = &font {
+        measuring_canvas = measuring_canvas.with_font(font.clone());
+    }
+    let legend_layout = LegendLayout::measure(
+        &measuring_canvas,
+        &legend,
+        &chart.legend_config(),
+        &theme,
+        (width, height),
+        chart.plot_inset(&theme),
+    );
+
+    let (image_width, image_height) = legend_layout.size;
+    let mut canvas = RasterCanvas::new(image_width, image_height, options.pixel_ratio)?;
+    if let Some(font) = font {
+        canvas = canvas.with_font(font);
+    }
+
+    canvas.save();
+    let (x, y) = legend_layout.chart;
+    canvas.translate(x, y);
+    chart.draw(&canvas, width, height, &theme);
+    canvas.restore();
+
+    legend_layout.draw(&canvas, &legend, &theme);
+
+    // Paint the background last, since charts clear their area before drawing
+    canvas.paint_behind(&options.background);
+
+    Ok(canvas.into_image())
+}
+
+/// The first common sans-serif font found in the usual system font directories.
+pub fn system_font() -> Option<FontArc> {
+    const CANDIDATES: [&str; 6] = [
+        "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf",
+        "/usr/share/fonts/TTF/DejaVuSans.ttf",
+        "/usr/share/fonts/dejavu/DejaVuSans.ttf",
+        "/usr/share/fonts/truetype/liberation/LiberationSans-Regular.ttf",
+        "/usr/share/fonts/liberation/LiberationSans-Regular.ttf",
+        "C:\\Windows\\Fonts\\arial.ttf",
+    ];
+
+    CANDIDATES.iter().find_map(|path| {
+        let data = std::fs::read(path).ok()?;
+        FontArc::try_from_vec(data).ok()
+    })
+}
+
+/// A rendered chart.
+#[derive(Clone, Debug)]
+pub struct RasterImage {
+    pixmap: Pixmap,
+}
+
+impl RasterImage {
+    /// Width in pixels.
+    pub fn width(&self) -> u32 {
+        self.pixmap.width()
+    }
+
+    /// Height in pixels.
+    pub fn height(&self) -> u32 {
+        self.pixmap.height()
+    }
+
+    /// The pixels as RGBA bytes, row by row from the top-left, without premultiplied alpha.
+    pub fn to_rgba(&self) -> Vec<u8> {
+        self.pixmap
+            .pixels()
+            .iter()
+            .flat_map(|pixel| {
+                let color = pixel.demultiply();
+                [color.red(), color.green(), color.blue(), color.alpha()]
+            })
+            .collect()
+    }
+
+    /// The image encoded as a PNG file.
+    pub fn to_png(&self) -> Result<Vec<u8>, RasterError> {
+        self.pixmap
+            .encode_png()
+            .map_err(|error| RasterError::Png(error.to_string()))
+    }
+
+    /// Writes the image to `path` as a PNG file.
+    pub fn save_png(&self, path: impl AsRef<Path>) -> Result<(), RasterError> {
+        self.pixmap
+            .save_png(path)
+            .map_err(|error| RasterError::Png(error.to_string()))
+    }
+}
+
+/// A [`Canvas`] that draws into an image on the CPU.
+///
+/// Colors must be plain CSS colors: hex, `rgb()`/`rgba()` or a common color name. Like on a
+/// canvas, setting a color that can't be parsed keeps the previous one.
+#[derive(Debug)]
+pub struct RasterCanvas {
+    inner: RefCell<Inner>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    pixmap: Pixmap,
+    font: Option<FontArc>,
+    state: State,
+    saved: Vec<State>,
+    /// The current path, in image pixels.
+    path: Vec<Segment>,
+    has_current_point: bool,
+}
+
+#[derive(Clone, Debug)]
+struct State {
+    fill: Color,
+    stroke: Color,
+    line_width: f64,
+    alpha: f64,
+    line_dash: Vec<f64>,
+    font_size: f64,
+    text_align: String,
+    text_baseline: String,
+    /// Maps canvas coordinates to image pixels, including the pixel ratio.
+    transform: Transform,
+    clip: Option<Rc<Mask>>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Segment {
+    MoveTo(f32, f32),
+    LineTo(f32, f32),
+    QuadTo(f32, f32, f32, f32),
+    CubicTo(f32, f32, f32, f32, f32, f32),
+    Close,
+}
+
+impl RasterCanvas {
+    /// A transparent canvas of `width` by `height` CSS pixels, drawn at `pixel_ratio` image
+    /// pixels per CSS pixel.
+    pub fn new(width: f64, height: f64, pixel_ratio: f64) -> Result<Self, RasterError> {
+        let pixmap = Pixmap::new(
+            (width * pixel_ratio).round() as u32,
+            (height * pixel_ratio).round() as u32,
+        )
+        .ok_or(RasterError::EmptyImage)?;
+
+        // The initial values of a canvas context
+        let state = State {
+            fill: Color::BLACK,
+            stroke: Color::BLACK,
+            line_width: 1.0,
+            alpha: 1.0,
+            line_dash: Vec::new(),
+            font_size: 10.0,
+            text_align: "start".into(),
+            text_baseline: "alphabetic".into(),
+            transform: Transform {
+                a: pixel_ratio,
+                d: pixel_ratio,
+                ..Transform::default()
+            },
+            clip: None,
+        };
+
+        Ok(Self {
+            inner: RefCell::new(Inner {
+                pixmap,
+                font: None,
+                state,
+                saved: Vec::new(),
+                path: Vec::new(),
+                has_current_point: false,
+            }),
+        })
+    }
+
+    /// Draws text in `font`. Without a font, text isn't drawn at all.
+    pub fn with_font(self, font: FontArc) -> Self {
+        self.inner.borrow_mut().font = Some(font);
+        self
+    }
+
+    /// Everything drawn so far.
+    pub fn into_image(self) -> RasterImage {
+        RasterImage {
+            pixmap: self.inner.into_inner().pixmap,
+        }
+    }
+
+    /// Paints `color` behind everything drawn so far.
+    fn paint_behind(&self, color: &str) {
+        let Some(color) = parse_color(color) else {
+            return;
+        };
+
+        let mut inner = self.inner.borrow_mut();
+        let (width, height) = (inner.pixmap.width(), inner.pixmap.height());
+        let Some(rect) = Rect::from_xywh(0.0, 0.0, width as f32, height as f32) else {
+            return;
+        };
+
+        let mut paint = Paint::default();
+        paint.set_color(color);
+        paint.blend_mode = BlendMode::DestinationOver;
+        inner
+            .pixmap
+            .fill_rect(rect, &paint, tiny_skia::Transform::identity(), None);
+    }
+}
+
+impl Inner {
+    fn point(&self, x: f64, y: f64) -> (f32, f32) {
+        let (x, y) = self.state.transform.apply(x, y);
+        (x as f32, y as f32)
+    }
+
+    fn path_to(&mut self, x: f64, y: f64) {
+        let (x, y) = self.point(x, y);
+        if self.has_current_point {
+            self.path.push(Segment::LineTo(x, y));
+        } else {
+            self.path.push(Segment::MoveTo(x, y));
+        }
+        self.has_current_point = true;
+    }
+
+    fn arc(&mut self, x: f64, y: f64, radius: f64, start_angle: f64, sweep: f64) {
+        let ((start_x, start_y), curves) = arc_curves(x, y, radius, start_angle, sweep);
+        self.path_to(start_x, start_y);
+
+        for [(cp1x, cp1y), (cp2x, cp2y), (end_x, end_y)] in curves {
+            let (cp1x, cp1y) = self.point(cp1x, cp1y);
+            let (cp2x, cp2y) = self.point(cp2x, cp2y);
+            let (end_x, end_y) = self.point(end_x, end_y);
+            self.path
+                .push(Segment::CubicTo(cp1x, cp1y, cp2x, cp2y, end_x, end_y));
+        }
+    }
+
+    /// The segments of a rectangle, without touching the current path.
+    fn rect_segments(&self, x: f64, y: f64, width: f64, height: f64) -> [Segment; 5] {
+        let (x1, y1) = self.point(x, y);
+        let (x2, y2) = self.point(x + width, y);
+        let (x3, y3) = self.point(x + width, y + height);
+        let (x4, y4) = self.point(x, y + height);
+
+        [
+            Segment::MoveTo(x1, y1),
+            Segment::LineTo(x2, y2),
+            Segment::LineTo(x3, y3),
+            Segment::LineTo(x4, y4),
+            Segment::Close,
+        ]
+    }
+
+    fn fill_segments(&mut self, segments: &[Segment], shader: Shader, blend_mode: BlendMode) {
+        let Some(path) = build_path(segments) else {
+            return;
+        };
+
+        let paint = Paint {
+            shader,
+            blend_mode,
+            anti_alias: true,
+            ..Default::default()
+        };
+        self.pixmap.fill_path(
+            &path,
+            &paint,
+            FillRule::Winding,
+            tiny_skia::Transform::identity(),
+            self.state.clip.as_deref(),
+        );
+    }
+
+    fn fill(&mut self, segments: &[Segment]) {
+        let mut color = self.state.fill;
+        color.apply_opacity(self.state.alpha as f32);
+        self.fill_segments(segments, Shader::SolidColor(color), BlendMode::SourceOver);
+    }
+
+    fn stroke(&mut self, segments: &[Segment]) {
+        let Some(path) = build_path(segments) else {
+            return;
+        };
+
+        let mut color = self.state.stroke;
+        color.apply_opacity(self.state.alpha as f32);
+        let mut paint = Paint::default();
+        paint.set_color(color);
+        paint.anti_alias = true;
+
+        // Transforms only ever rotate, translate and scale evenly, so this is the line's scale
+        let transform = &self.state.transform;
+        let scale = (transform.a * transform.a + transform.b * transform.b).sqrt();
+        let stroke = Stroke {
+            width: (self.state.line_width * scale) as f32,
+            miter_limit: 10.0,
+            dash: StrokeDash::new(
+                self.state
+                    .line_dash
+                    .iter()
+                    .map(|&segment| (segment * scale) as f32)
+                    .collect(),
+                0.0,
+            ),
+            ..Default::default()
+        };
+        self.pixmap.stroke_path(
+            &path,
+            &paint,
+            &stroke,
+            tiny_skia::Transform::identity(),
+            self.state.clip.as_deref(),
+        );
+    }
+
+    /// The segments for `text` at `(x, y)`, aligned like on a canvas.
+    fn text_segments(&self, font: &FontArc, text: &str, x: f64, y: f64) -> Vec<Segment> {
+        let scale = self.state.font_size / units_per_em(font);
+        let ascent = font.ascent_unscaled() as f64 * scale;
+        let descent = font.descent_unscaled() as f64 * scale;
+
+        let x = x - match self.state.text_align.as_str() {
+            "center" => text_width(font, self.state.font_size, text) / 2.0,
+            "right" | "end" => text_width(font, self.state.font_size, text),
+            _ => 0.0,
+        };
+        let baseline = y + match self.state.text_baseline.as_str() {
+            "top" | "hanging" => ascent,
+            "middle" => (ascent + descent) / 2.0,
+            "bottom" | "ideographic" => descent,
+            _ => 0.0,
+        };
+
+        let mut segments = Vec::new();
+        let mut pen = x;
+        let mut previous = None;
+        for c in text.chars() {
+            let glyph = font.glyph_id(c);
+            if let Some(previous) = previous {
+                pen += font.kern_unscaled(previous, glyph) as f64 * scale;
+            }
+            previous = Some(glyph);
+
+            // Glyph outlines are in font units, with y pointing up
+            let to_canvas = |point: ab_glyph::Point| {
+                self.point(
+                    pen + point.x as f64 * scale,
+                    baseline - point.y as f64 * scale,
+                )
+            };
+            let mut contour_end = None;
+            for curve in font
+                .outline(glyph)
+                .map(|outline| outline.curves)
+                .unwrap_or_default()
+            {
+                let (start, end) = match curve {
+                    OutlineCurve::Line(start, end)
+                    | OutlineCurve::Quad(start, _, end)
+                    | OutlineCurve::Cubic(start, _, _, end) => (start, end),
+                };
+                if contour_end != Some(start) {
+                    if contour_end.is_some() {
+                        segments.push(Segment::Close);
+                    }
+                    let (x, y) = to_canvas(start);
+                    segments.push(Segment::MoveTo(x, y));
+                }
+                contour_end = Some(end);
+
+                segments.push(match curve {
+                    OutlineCurve::Line(_, end) => {
+                        let (x, y) = to_canvas(end);
+                        Segment::LineTo(x, y)
+                    }
+                    OutlineCurve::Quad(_, control, end) => {
+                        let (cx, cy) = to_canvas(control);
+                        let (x, y) = to_canvas(end);
+                        Segment::QuadTo(cx, cy, x, y)
+                    }
+                    OutlineCurve::Cubic(_, control_1, control_2, end) => {
+                        let (c1x, c1y) = to_canvas(control_1);
+                        let (c2x, c2y) = to_canvas(control_2);
+                        let (x, y) = to_canvas(end);
+                        Segment::CubicTo(c1x, c1y, c2x, c2y, x, y)
+                    }
+                });
+            }
+            if contour_end.is_some() {
+                segments.push(Segment::Close);
+            }
+
+            pen += font.h_advance_unscaled(glyph) as f64 * scale;
+        }
+
+        segments
+    }
+}
+
+impl Canvas for RasterCanvas {
+    fn save(&self) {
+        let mut inner = self.inner.borrow_mut();
+        let state = inner.state.clone();
+        inner.saved.push(state);
+    }
+
+    fn restore(&self) {
+        let mut inner = self.inner.borrow_mut();
+        if let Some(state) = inner.saved.pop() {
+            inner.state = state;
+        }
+    }
+
+    fn translate(&self, x: f64, y: f64) {
+        self.inner.borrow_mut().state.transform.translate(x, y);
+    }
+
+    fn rotate(&self, angle: f64) {
+        self.inner.borrow_mut().state.transform.rotate(angle);
+    }
+
+    fn set_fill_style(&self, color: &str) {
+        if let Some(color) = parse_color(color) {
+            self.inner.borrow_mut().state.fill = color;
+        }
+    }
+
+    fn set_stroke_style(&self, color: &str) {
+        if let Some(color) = parse_color(color) {
+            self.inner.borrow_mut().state.stroke = color;
+        }
+    }
+
+    fn set_line_width(&self, width: f64) {
+        self.inner.borrow_mut().state.line_width = width;
+    }
+
+    fn set_global_alpha(&self, alpha: f64) {
+        self.inner.borrow_mut().state.alpha = alpha;
+    }
+
+    fn set_line_dash(&self, segments: &[f64]) {
+        self.inner.borrow_mut().state.line_dash = segments.to_vec();
+    }
+
+    fn set_font(&self, font: &str) {
+        self.inner.borrow_mut().state.font_size = font_size(font);
+    }
+
+    fn set_text_align(&self, align: &str) {
+        self.inner.borrow_mut().state.text_align = align.to_string();
+    }
+
+    fn set_text_baseline(&self, baseline: &str) {
+        self.inner.borrow_mut().state.text_baseline = baseline.to_string();
+    }
+
+    fn clear_rect(&self, x: f64, y: f64, width: f64, height: f64) {
+        let mut inner = self.inner.borrow_mut();
+        let segments = inner.rect_segments(x, y, width, height);
+        inner.fill_segments(
+            &segments,
+            Shader::SolidColor(Color::BLACK),
+            BlendMode::Clear,
+        );
+    }
+
+    fn fill_rect(&self, x: f64, y: f64, width: f64, height: f64) {
+        let mut inner = self.inner.borrow_mut();
+        let segments = inner.rect_segments(x, y, width, height);
+        inner.fill(&segments);
+    }
+
+    fn stroke_rect(&self, x: f64, y: f64, width: f64, height: f64) {
+        let mut inner = self.inner.borrow_mut();
+        let segments = inner.rect_segments(x, y, width, height);
+        inner.stroke(&segments);
+    }
+
+    fn fill_text(&self, text: &str, x: f64, y: f64) {
+        let mut inner = self.inner.borrow_mut();
+        let Some(font) = inner.font.clone() else {
+            return;
+        };
+
+        let segments = inner.text_segments(&font, text, x, y);
+        inner.fill(&segments);
+    }
+
+    /// Uses the font's metrics, or estimates the width from the font size without a font.
+    fn measure_text(&self, text: &str) -> f64 {
+        let inner = self.inner.borrow();
+        match &inner.font {
+            Some(font) => text_width(font, inner.state.font_size, text),
+            None => inner.state.font_size * 0.6 * text.chars().count() as f64,
+        }
+    }
+
+    fn begin_path(&self) {
+        let mut inner = self.inner.borrow_mut();
+        inner.path.clear();
+        inner.has_current_point = false;
+    }
+
+    fn close_path(&self) {
+        let mut inner = self.inner.borrow_mut();
+        if inner.has_current_point {
+            inner.path.push(Segment::Close);
+        }
+    }
+
+    fn move_to(&self, x: f64, y: f64) {
+        let mut inner = self.inner.borrow_mut();
+        let (x, y) = inner.point(x, y);
+        inner.path.push(Segment::MoveTo(x, y));
+        inner.has_current_point = true;
+    }
+
+    fn line_to(&self, x: f64, y: f64) {
+        self.inner.borrow_mut().path_to(x, y);
+    }
+
+    fn bezier_curve_to(&self, cp1x: f64, cp1y: f64, cp2x: f64, cp2y: f64, x: f64, y: f64) {
+        let mut inner = self.inner.borrow_mut();
+        if !inner.has_current_point {
+            inner.path_to(cp1x, cp1y);
+        }
+        let (cp1x, cp1y) = inner.point(cp1x, cp1y);
+        let (cp2x, cp2y) = inner.point(cp2x, cp2y);
+        let (x, y) = inner.point(x, y);
+        inner
+            .path
+            .push(Segment::CubicTo(cp1x, cp1y, cp2x, cp2y, x, y));
+    }
+
+    fn arc(&self, x: f64, y: f64, radius: f64, start_angle: f64, end_angle: f64) {
+        let sweep = arc_sweep(start_angle, end_angle, false);
+        self.inner
+            .borrow_mut()
+            .arc(x, y, radius, start_angle, sweep);
+    }
+
+    fn arc_anticlockwise(&self, x: f64, y: f64, radius: f64, start_angle: f64, end_angle: f64) {
+        let sweep = arc_sweep(start_angle, end_angle, true);
+        self.inner
+            .borrow_mut()
+            .arc(x, y, radius, start_angle, sweep);
+    }
+
+    fn rect(&self, x: f64, y: f64, width: f64, height: f64) {
+        let mut inner = self.inner.borrow_mut();
+        let segments = inner.rect_segments(x, y, width, height);
+        inner.path.extend(segments);
+        inner.has_current_point = true;
+    }
+
+    fn fill(&self) {
+        let mut inner = self.inner.borrow_mut();
+        let path = std::mem::take(&mut inner.path);
+        inner.fill(&path);
+        inner.path = path;
+    }
+
+    fn stroke(&self) {
+        let mut inner = self.inner.borrow_mut();
+        let path = std::mem::take(&mut inner.path);
+        inner.stroke(&path);
+        inner.path = path;
+    }
+
+    fn clip(&self) {
+        let mut inner = self.inner.borrow_mut();
+        let Some(path) = build_path(&inner.path) else {
+            return;
+        };
+
+        // Clipping inside a clip intersects the two, like on a canvas
+        let identity = tiny_skia::Transform::identity();
+        let mask = match &inner.state.clip {
+            Some(clip) => {
+                let mut mask = Mask::clone(clip);
+                mask.intersect_path(&path, FillRule::Winding, true, identity);
+                Some(mask)
+            }
+            None => Mask::new(inner.pixmap.width(), inner.pixmap.height()).map(|mut mask| {
+                mask.fill_path(&path, FillRule::Winding, true, identity);
+                mask
+            }),
+        };
+        inner.state.clip = mask.map(Rc::new);
+    }
+
+    fn fill_pattern(&self, pattern: Pattern, ink: &str) {
+        let mut inner = self.inner.borrow_mut();
+
+        // Draw the tile at the image's pixel density, the same way as on screen
+        let transform = &inner.state.transform;
+        let pixel_ratio = (transform.a * transform.a + transform.b * transform.b).sqrt();
+        let Ok(tile) = RasterCanvas::new(TILE_SIZE, TILE_SIZE, pixel_ratio) else {
+            return;
+        };
+        pattern.draw_tile(&tile, ink);
+        let tile = tile.into_image().pixmap;
+
+        let shader = tiny_skia::Pattern::new(
+            tile.as_ref(),
+            SpreadMode::Repeat,
+            FilterQuality::Nearest,
+            inner.state.alpha as f32,
+            tiny_skia::Transform::identity(),
+        );
+        let path = std::mem::take(&mut inner.path);
+        inner.fill_segments(&path, shader, BlendMode::SourceOver);
+        inner.path = path;
+    }
+}
+
+fn build_path(segments: &[Segment]) -> Option<tiny_skia::Path> {
+    let mut builder = PathBuilder::new();
+    for segment in segments {
+        match *segment {
+            Segment::MoveTo(x, y) => builder.move_to(x, y),
+            Segment::LineTo(x, y) => builder.line_to(x, y),
+            Segment::QuadTo(cx, cy, x, y) => builder.quad_to(cx, cy, x, y),
+            Segment::CubicTo(c1x, c1y, c2x, c2y, x, y) => {
+                builder.cubic_to(c1x, c1y, c2x, c2y, x, y)
+            }
+            Segment::Close => builder.close(),
+        }
+    }
+
+    builder.finish()
+}
+
+fn units_per_em(font: &FontArc) -> f64 {
+    font.units_per_em().map_or(1000.0, |units| units as f64)
+}
+
+/// The width of `text` in `font` at `size` pixels, including kerning.
+fn text_width(font: &FontArc, size: f64, text: &str) -> f64 {
+    let mut width = 0.0;
+    let mut previous = None;
+    for c in text.chars() {
+        let glyph = font.glyph_id(c);
+        if let Some(previous) = previous {
+            width += font.kern_unscaled(previous, glyph) as f64;
+        }
+        width += font.h_advance_unscaled(glyph) as f64;
+        previous = Some(glyph);
+    }
+
+    width * size / units_per_em(font)
+}
+
+/// Parses a CSS color: `#rgb`, `#rgba`, `#rrggbb`, `#rrggbbaa`, `rgb()`, `rgba()` or a common
+/// color name.
+fn parse_color(color: &str) -> Option<Color> {
+    let color = color.trim().to_ascii_lowercase();
+
+    if let Some(hex) = color.strip_prefix('#') {
+        let digits = hex
+            .chars()
+            .map(|c| c.to_digit(16).map(|digit| digit as u8))
+            .collect::<Option<Vec<u8>>>()?;
+        let [r, g, b, a] = match digits[..] {
+            [r, g, b] => [r * 17, g * 17, b * 17, 255],
+            [r, g, b, a] => [r * 17, g * 17, b * 17, a * 17],
+            [r1, r2, g1, g2, b1, b2] => [r1 * 16 + r2, g1 * 16 + g2, b1 * 16 + b2, 255],
+            [r1, r2, g1, g2, b1, b2, a1, a2] => {
+                [r1 * 16 + r2, g1 * 16 + g2, b1 * 16 + b2, a1 * 16 + a2]
+            }
+            _ => return None,
+        };
+        return Some(Color::from_rgba8(r, g, b, a));
+    }
+
+    let arguments = color
+        .strip_prefix("rgba(")
+        .or_else(|| color.strip_prefix("rgb("))
+        .and_then(|rest| rest.strip_suffix(')'));
+    if let Some(arguments) = arguments {
+        let values = arguments
+            .split(|c: char| c == ',' || c == '/' || c.is_whitespace())
+            .filter(|value| !value.is_empty())
+            .collect::<Vec<&str>>();
+        let channel = |value: &str| -> Option<u8> {
+            let value = match value.strip_suffix('%') {
+                Some(percent) => percent.parse::<f64>().ok()? * 2.55,
+                None => value.parse::<f64>().ok()?,
+            };
+            Some(value.round().clamp(0.0, 255.0) as u8)
+        };
+        let alpha = |value: &str| -> Option<f32> {
+            let value = match value.strip_suffix('%') {
+                Some(percent) => percent.parse::<f32>().ok()? / 100.0,
+                None => value.parse::<f32>().ok()?,
+            };
+            Some(value.clamp(0.0, 1.0))
+        };
+
+        let (r, g, b, a) = match values[..] {
+            [r, g, b] => (channel(r)?, channel(g)?, channel(b)?, 1.0),
+            [r, g, b, a] => (channel(r)?, channel(g)?, channel(b)?, alpha(a)?),
+            _ => return None,
+        };
+        return Some(Color::from_rgba8(r, g, b, (a * 255.0).round() as u8));
+    }
+
+    let [r, g, b, a] = match color.as_str() {
+        "transparent" => [0, 0, 0, 0],
+        "black" => [0, 0, 0, 255],
+        "white" => [255, 255, 255, 255],
+        "red" => [255, 0, 0, 255],
+        "green" => [0, 128, 0, 255],
+        "lime" => [0, 255, 0, 255],
+        "blue" => [0, 0, 255, 255],
+        "yellow" => [255, 255, 0, 255],
+        "orange" => [255, 165, 0, 255],
+        "purple" => [128, 0, 128, 255],
+        "pink" => [255, 192, 203, 255],
+        "brown" => [165, 42, 42, 255],
+        "cyan" | "aqua" => [0, 255, 255, 255],
+        "magenta" | "fuchsia" => [255, 0, 255, 255],
+        "gray" | "grey" => [128, 128, 128, 255],
+        "lightgray" | "lightgrey" => [211, 211, 211, 255],
+        "darkgray" | "darkgrey" => [169, 169, 169, 255],
+        "silver" => [192, 192, 192, 255],
+        "maroon" => [128, 0, 0, 255],
+        "olive" => [128, 128, 0, 255],
+        "teal" => [0, 128, 128, 255],
+        "navy" => [0, 0, 128, 255],
+        _ => return None,
+    };
+    Some(Color::from_rgba8(r, g, b, a))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::charts::doughnut_chart::doughnut_chart::{DoughnutChartConfigs, DoughnutChartProps};
+    use crate::charts::pie_chart::pie_chart::{DataPoint, PieChartConfig, PieChartProps};
+
+    fn pixel(image: &RasterImage, x: u32, y: u32) -> [u8; 4] {
+        let index = ((y * image.width() + x) * 4) as usize;
+        image.to_rgba()[index..index + 4].try_into().unwrap()
+    }
+
+    fn pie_chart() -> PieChartProps {
+        PieChartProps {
+            data: vec![
+                DataPoint::new("A", 10, "#ff0000"),
+                DataPoint::new("B", 20, "#0000ff").with_pattern(Pattern::Dots),
+            ],
+            config: PieChartConfig {
+                show_legend: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn parses_css_colors() {
+        let rgba = |color: &str| parse_color(color).map(|color| color.to_color_u8());
+
+        assert_eq!(rgba("#f00"), rgba("rgb(255, 0, 0)"));
+        assert_eq!(rgba("#36a2eb"), rgba("rgb(54 162 235)"));
+        assert_eq!(rgba("#00000080"), rgba("rgba(0, 0, 0, 0.5)"));
+        assert_eq!(rgba("Gray"), rgba("#808080"));
+        assert_eq!(rgba("transparent").unwrap().alpha(), 0);
+        assert_eq!(rgba("var(--chart-color)"), None);
+        assert_eq!(rgba("#12345"), None);
+    }
+
+    #[test]
+    fn fills_and_clears_through_transforms() {
+        let canvas = RasterCanvas::new(10.0, 10.0, 2.0).unwrap();
+        canvas.set_fill_style("red");
+        canvas.fill_rect(0.0, 0.0, 10.0, 10.0);
+        canvas.translate(5.0, 0.0);
+        canvas.clear_rect(0.0, 0.0, 5.0, 10.0);
+
+        let image = canvas.into_image();
+        assert_eq!((image.width(), image.height()), (20, 20));
+        assert_eq!(pixel(&image, 5, 10), [255, 0, 0, 255]);
+        assert_eq!(pixel(&image, 15, 10), [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn global_alpha_fades_what_is_drawn() {
+        let canvas = RasterCanvas::new(10.0, 10.0, 1.0).unwrap();
+        canvas.set_fill_style("red");
+        canvas.set_global_alpha(0.5);
+        canvas.fill_rect(0.0, 0.0, 5.0, 10.0);
+        canvas.set_global_alpha(1.0);
+        canvas.fill_rect(5.0, 0.0, 5.0, 10.0);
+
+        let image = canvas.into_image();
+        assert!((127..=128).contains(&pixel(&image, 2, 5)[3]));
+        assert_eq!(pixel(&image, 7, 5)[3], 255);
+    }
+
+    #[test]
+    fn clips_intersect() {
+        let canvas = RasterCanvas::new(10.0, 10.0, 1.0).unwrap();
+        canvas.begin_path();
+        canvas.rect(0.0, 0.0, 6.0, 10.0);
+        canvas.clip();
+        canvas.begin_path();
+        canvas.rect(4.0, 0.0, 6.0, 10.0);
+        canvas.clip();
+        canvas.fill_rect(0.0, 0.0, 10.0, 10.0);
+
+        let image = canvas.into_image();
+        assert_eq!(pixel(&image, 1, 5)[3], 0);
+        assert_eq!(pixel(&image, 5, 5), [0, 0, 0, 255]);
+        assert_eq!(pixel(&image, 8, 5)[3], 0);
+    }
+
+    #[test]
+    fn renders_charts_over_the_background() {
+        let options = RasterOptions {
+            background: "#00ff00".into(),
+            ..Default::default()
+        };
+        let image = render(&pie_chart(), 200.0, 100.0, &options).unwrap();
+
+        assert_eq!(image.width(), 200);
+        assert!(image.height() > 100, "the legend adds to the height");
+        assert_eq!(pixel(&image, 199, 0), [0, 255, 0, 255]);
+
+        let rgba = image.to_rgba();
+        assert_eq!(rgba.len(), 200 * image.height() as usize * 4);
+        assert!(rgba.chunks(4).any(|pixel| pixel == [255, 0, 0, 255]));
+        assert!(rgba.chunks(4).any(|pixel| pixel == [0, 0, 255, 255]));
+    }
+
+    #[test]
+    fn draws_text_with_a_font() {
+        let Some(font) = system_font() else {
+            return;
+        };
+        let text_pixels = |font: Option<FontArc>| {
+            let mut canvas = RasterCanvas::new(40.0, 20.0, 1.0).unwrap();
+            if let Some(font) = font {
+                canvas = canvas.with_font(font);
+            }
+            canvas.set_font("bold 12px Arial");
+            canvas.set_text_baseline("top");
+            canvas.fill_text("Hi", 2.0, 2.0);
+            canvas
+                .into_image()
+                .to_rgba()
+                .chunks(4)
+                .filter(|pixel| pixel[3] > 0)
+                .count()
+        };
+
+        assert!(text_pixels(Some(font.clone())) > 0);
+        assert_eq!(text_pixels(None), 0);
+
+        let canvas = RasterCanvas::new(1.0, 1.0, 1.0).unwrap().with_font(font);
+        canvas.set_font("10px sans-serif");
+        let narrow = canvas.measure_text("ii");
+        assert!(narrow > 0.0 && narrow < canvas.measure_text("WW"));
+    }
+
+    #[test]
+    fn encodes_png() {
+        let props = DoughnutChartProps {
+            data: vec![
+                ("A".to_string(), 30, "".to_string()),
+                ("B".to_string(), 70, "".to_string()),
+            ],
+            config: DoughnutChartConfigs::default(),
+            ..Default::default()
+        };
+        let image = render(&props, 100.0, 100.0, &RasterOptions::default()).unwrap();
+
+        assert!(image.to_png().unwrap().starts_with(b"\x89PNG\r\n\x1a\n"));
+        assert!(matches!(
+            render(&props, 0.0, 100.0, &RasterOptions::default()),
+            Err(RasterError::EmptyImage)
+        ));
+    }
+}
@@ -0,0 +1,484 @@
+use std::cell::RefCell;
+use std::fmt;
+
+use crate::charts::canvas::Canvas;
+use crate::charts::pattern::Pattern;
+use crate::charts::text::{estimated_text_width, number};
+
+/// One call made on a [`RecordingCanvas`], with its arguments.
+#[derive(Clone, Debug, PartialEq)]
+pub enum DrawCommand {
+    Save,
+    Restore,
+    Translate {
+        x: f64,
+        y: f64,
+    },
+    Rotate {
+        angle: f64,
+    },
+    SetFillStyle(String),
+    SetStrokeStyle(String),
+    SetLineWidth(f64),
+    SetGlobalAlpha(f64),
+    SetLineDash(Vec<f64>),
+    SetFont(String),
+    SetTextAlign(String),
+    SetTextBaseline(String),
+    ClearRect {
+        x: f64,
+        y: f64,
+        width: f64,
+        height: f64,
+    },
+    FillRect {
+        x: f64,
+        y: f64,
+        width: f64,
+        height: f64,
+    },
+    StrokeRect {
+        x: f64,
+        y: f64,
+        width: f64,
+        height: f64,
+    },
+    FillText {
+        text: String,
+        x: f64,
+        y: f64,
+    },
+    BeginPath,
+    ClosePath,
+    MoveTo {
+        x: f64,
+        y: f64,
+    },
+    LineTo {
+        x: f64,
+        y: f64,
+    },
+    BezierCurveTo {
+        cp1x: f64,
+        cp1y: f64,
+        cp2x: f64,
+        cp2y: f64,
+        x: f64,
+        y: f64,
+    },
+    Arc {
+        x: f64,
+        y: f64,
+        radius: f64,
+        start_angle: f64,
+        end_angle: f64,
+        anticlockwise: bool,
+    },
+    Rect {
+        x: f64,
+        y: f64,
+        width: f64,
+        height: f64,
+    },
+    Fill,
+    Stroke,
+    Clip,
+    FillPattern {
+        pattern: Pattern,
+        ink: String,
+    },
+}
+
+/// One command per line, with numbers rounded to two decimals, e.g. `fill_rect 50 80 120 270`.
+impl fmt::Display for DrawCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let numbers = |values: &[f64]| {
+            values
+                .iter()
+                .map(|&value| number(value))
+                .collect::<Vec<String>>()
+                .join(" ")
+        };
+
+        match self {
+            DrawCommand::Save => write!(f, "save"),
+            DrawCommand::Restore => write!(f, "restore"),
+            DrawCommand::Translate { x, y } => write!(f, "translate {}", numbers(&[*x, *y])),
+            DrawCommand::Rotate { angle } => write!(f, "rotate {}", number(*angle)),
+            DrawCommand::SetFillStyle(color) => write!(f, "fill_style {}", color),
+            DrawCommand::SetStrokeStyle(color) => write!(f, "stroke_style {}", color),
+            DrawCommand::SetLineWidth(width) => write!(f, "line_width {}", number(*width)),
+            DrawCommand::SetGlobalAlpha(alpha) => write!(f, "global_alpha {}", number(*alpha)),
+            DrawCommand::SetLineDash(segments) => write!(f, "line_dash {}", numbers(segments)),
+            DrawCommand::SetFont(font) => write!(f, "font {}", font),
+            DrawCommand::SetTextAlign(align) => write!(f, "text_align {}", align),
+            DrawCommand::SetTextBaseline(baseline) => write!(f, "text_baseline {}", baseline),
+            DrawCommand::ClearRect {
+                x,
+                y,
+                width,
+                height,
+            } => write!(f, "clear_rect {}", numbers(&[*x, *y, *width, *height])),
+            DrawCommand::FillRect {
+                x,
+                y,
+                width,
+                height,
+            } => write!(f, "fill_rect {}", numbers(&[*x, *y, *width, *height])),
+            DrawCommand::StrokeRect {
+                x,
+                y,
+                width,
+                height,
+            } => write!(f, "stroke_rect {}", numbers(&[*x, *y, *width, *height])),
+            DrawCommand::FillText { text, x, y } => {
+                write!(f, "fill_text {:?} {}", text, numbers(&[*x, *y]))
+            }
+            DrawCommand::BeginPath => write!(f, "begin_path"),
+            DrawCommand::ClosePath => write!(f, "close_path"),
+            DrawCommand::MoveTo { x, y } => write!(f, "move_to {}", numbers(&[*x, *y])),
+            DrawCommand::LineTo { x, y } => write!(f, "line_to {}", numbers(&[*x, *y])),
+            DrawCommand::BezierCurveTo {
+                cp1x,
+                cp1y,
+                cp2x,
+                cp2y,
+                x,
+                y,
+            } => write!(
+                f,
+                "bezier_curve_to {}",
+                numbers(&[*cp1x, *cp1y, *cp2x, *cp2y, *x, *y])
+            ),
+            DrawCommand::Arc {
+                x,
+                y,
+                radius,
+                start_angle,
+                end_angle,
+                anticlockwise,
+            } => write!(
+                f,
+                "{} {}",
+                if *anticlockwise {
+                    "arc_anticlockwise"
+                } else {
+                    "arc"
+                },
+                numbers(&[*x, *y, *radius, *start_angle, *end_angle])
+            ),
+            DrawCommand::Rect {
+                x,
+                y,
+                width,
+                height,
+            } => write!(f, "rect {}", numbers(&[*x, *y, *width, *height])),
+            DrawCommand::Fill => write!(f, "fill"),
+            DrawCommand::Stroke => write!(f, "stroke"),
+            DrawCommand::Clip => write!(f, "clip"),
+            DrawCommand::FillPattern { pattern, ink } => {
+                write!(f, "fill_pattern {:?} {}", pattern, ink)
+            }
+        }
+    }
+}
+
+/// A [`Canvas`] that draws nothing and records every call instead, for testing charts without
+/// a browser.
+///
+/// Text is measured like on [`crate::svg::SvgCanvas`], from the font size alone, so layouts
+/// come out the same on every machine.
+#[derive(Debug, Default)]
+pub struct RecordingCanvas {
+    inner: RefCell<Inner>,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    commands: Vec<DrawCommand>,
+    /// The current font and the fonts of the saved states, for measuring text.
+    font: Option<String>,
+    saved_fonts: Vec<Option<String>>,
+}
+
+impl RecordingCanvas {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Everything recorded so far, in order.
+    pub fn commands(&self) -> Vec<DrawCommand> {
+        self.inner.borrow().commands.clone()
+    }
+
+    /// Everything recorded so far as text, one command per line, for comparing against a
+    /// snapshot file.
+    pub fn to_snapshot(&self) -> String {
+        self.inner
+            .borrow()
+            .commands
+            .iter()
+            .map(|command| format!("{}\n", command))
+            .collect()
+    }
+
+    fn record(&self, command: DrawCommand) {
+        self.inner.borrow_mut().commands.push(command);
+    }
+}
+
+impl Canvas for RecordingCanvas {
+    fn save(&self) {
+        let mut inner = self.inner.borrow_mut();
+        let font = inner.font.clone();
+        inner.saved_fonts.push(font);
+        inner.commands.push(DrawCommand::Save);
+    }
+
+    fn restore(&self) {
+        let mut inner = self.inner.borrow_mut();
+        if let Some(font) = inner.saved_fonts.pop() {
+            inner.font = font;
+        }
+        inner.commands.push(DrawCommand::Restore);
+    }
+
+    fn translate(&self, x: f64, y: f64) {
+        self.record(DrawCommand::Translate { x, y });
+    }
+
+    fn rotate(&self, angle: f64) {
+        self.record(DrawCommand::Rotate { angle });
+    }
+
+    fn set_fill_style(&self, color: &str) {
+        self.record(DrawCommand::SetFillStyle(color.to_string()));
+    }
+
+    fn set_stroke_style(&self, color: &str) {
+        self.record(DrawCommand::SetStrokeStyle(color.to_string()));
+    }
+
+    fn set_line_width(&self, width: f64) {
+        self.record(DrawCommand::SetLineWidth(width));
+    }
+
+    fn set_global_alpha(&self, alpha: f64) {
+        self.record(DrawCommand::SetGlobalAlpha(alpha));
+    }
+
+    fn set_line_dash(&self, segments: &[f64]) {
+        self.record(DrawCommand::SetLineDash(segments.to_vec()));
+    }
+
+    fn set_font(&self, font: &str) {
+        let mut inner = self.inner.borrow_mut();
+        inner.font = Some(font.to_string());
+        inner.commands.push(DrawCommand::SetFont(font.to_string()));
+    }
+
+    fn set_text_align(&self, align: &str) {
+        self.record(DrawCommand::SetTextAlign(align.to_string()));
+    }
+
+    fn set_text_baseline(&self, baseline: &str) {
+        self.record(DrawCommand::SetTextBaseline(baseline.to_string()));
+    }
+
+    fn clear_rect(&self, x: f64, y: f64, width: f64, height: f64) {
+        self.record(DrawCommand::ClearRect {
+            x,
+            y,
+            width,
+            height,
+        });
+    }
+
+    fn fill_rect(&self, x: f64, y: f64, width: f64, height: f64) {
+        self.record(DrawCommand::FillRect {
+            x,
+            y,
+            width,
+            height,
+        });
+    }
+
+    fn stroke_rect(&self, x: f64, y: f64, width: f64, height: f64) {
+        self.record(DrawCommand::StrokeRect {
+            x,
+            y,
+            width,
+            height,
+        });
+    }
+
+    fn fill_text(&self, text: &str, x: f64, y: f64) {
+        self.record(DrawCommand::FillText {
+            text: text.to_string(),
+            x,
+            y,
+        });
+    }
+
+    /// Not recorded, since measuring doesn't draw anything.
+    fn measure_text(&self, text: &str) -> f64 {
+        let inner = self.inner.borrow();
+        estimated_text_width(inner.font.as_deref().unwrap_or_default(), text)
+    }
+
+    fn begin_path(&self) {
+        self.record(DrawCommand::BeginPath);
+    }
+
+    fn close_path(&self) {
+        self.record(DrawCommand::ClosePath);
+    }
+
+    fn move_to(&self, x: f64, y: f64) {
+        self.record(DrawCommand::MoveTo { x, y });
+    }
+
+    fn line_to(&self, x: f64, y: f64) {
+        self.record(DrawCommand::LineTo { x, y });
+    }
+
+    fn bezier_curve_to(&self, cp1x: f64, cp1y: f64, cp2x: f64, cp2y: f64, x: f64, y: f64) {
+        self.record(DrawCommand::BezierCurveTo {
+            cp1x,
+            cp1y,
+            cp2x,
+            cp2y,
+            x,
+            y,
+        });
+    }
+
+    fn arc(&self, x: f64, y: f64, radius: f64, start_angle: f64, end_angle: f64) {
+        self.record(DrawCommand::Arc {
+            x,
+            y,
+            radius,
+            start_angle,
+            end_angle,
+            anticlockwise: false,
+        });
+    }
+
+    fn arc_anticlockwise(&self, x: f64, y: f64, radius: f64, start_angle: f64, end_angle: f64) {
+        self.record(DrawCommand::Arc {
+            x,
+            y,
+            radius,
+            start_angle,
+            end_angle,
+            anticlockwise: true,
+        });
+    }
+
+    fn rect(&self, x: f64, y: f64, width: f64, height: f64) {
+        self.record(DrawCommand::Rect {
+            x,
+            y,
+            width,
+            height,
+        });
+    }
+
+    fn fill(&self) {
+        self.record(DrawCommand::Fill);
+    }
+
+    fn stroke(&self) {
+        self.record(DrawCommand::Stroke);
+    }
+
+    fn clip(&self) {
+        self.record(DrawCommand::Clip);
+    }
+
+    fn fill_pattern(&self, pattern: Pattern, ink: &str) {
+        self.record(DrawCommand::FillPattern {
+            pattern,
+            ink: ink.to_string(),
+        });
+    }
+}
+
+/// Compares `actual` with the snapshot file `src/charts/snapshots/{name}.snap`.
+///
+/// A missing snapshot is written instead of compared, and running the tests with
+/// `UPDATE_SNAPSHOTS=1` rewrites every snapshot after an intended change. Review the new files
+/// before committing them.
+#[cfg(test)]
+pub(crate) fn assert_snapshot(name: &str, actual: &str) {
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("src/charts/snapshots")
+        .join(format!("{}.snap", name));
+
+    let expected = std::fs::read_to_string(&path).ok();
+    if expected.is_none() || std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, actual).unwrap();
+        return;
+    }
+
+    let expected = expected.unwrap();
+    if let Some((line, (expected, actual))) = expected
+        .lines()
+        .zip(actual.lines())
+        .enumerate()
+        .find(|(_, (expected, actual))| expected != actual)
+    {
+        panic!(
+            "snapshot {} differs at line {}:\n  expected: {}\n    actual: {}\n\
+             Rerun with UPDATE_SNAPSHOTS=1 if the change is intended.",
+            name,
+            line + 1,
+            expected,
+            actual
+        );
+    }
+    assert_eq!(
+        expected.lines().count(),
+        actual.lines().count(),
+        "snapshot {} has a different number of commands. \
+         Rerun with UPDATE_SNAPSHOTS=1 if the change is intended.",
+        name
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_commands_in_order() {
+        let canvas = RecordingCanvas::new();
+        canvas.begin_path();
+        canvas.arc_anticlockwise(10.0, 10.0, 5.0, 0.0, std::f64::consts::PI);
+        canvas.fill_pattern(Pattern::Dots, "black");
+        canvas.fill_text("Total", 1.0 / 3.0, 2.0);
+
+        assert_eq!(
+            canvas.to_snapshot(),
+            "begin_path\n\
+             arc_anticlockwise 10 10 5 0 3.14\n\
+             fill_pattern Dots black\n\
+             fill_text \"Total\" 0.33 2\n"
+        );
+    }
+
+    #[test]
+    fn measures_text_in_the_current_font() {
+        let canvas = RecordingCanvas::new();
+        assert_eq!(canvas.measure_text("abc"), 18.0);
+
+        canvas.save();
+        canvas.set_font("bold 20px Arial");
+        assert_eq!(canvas.measure_text("abc"), 36.0);
+        canvas.restore();
+
+        assert_eq!(canvas.measure_text("abc"), 18.0);
+        assert!(!canvas.commands().is_empty());
+    }
+}
@@ -0,0 +1,196 @@
+use gloo::events::EventListener;
+use gloo::render::{request_animation_frame, AnimationFrame};
+use std::cell::RefCell;
+use std::rc::{Rc, Weak};
+use web_sys::{
+    js_sys::Reflect,
+    wasm_bindgen::{closure::Closure, JsCast, JsValue},
+    window, CanvasRenderingContext2d, Element, HtmlCanvasElement, ResizeObserver,
+};
+
+/// Keeps a chart redrawing while its container changes size or it moves to a screen with a
+/// different pixel density. Dropping it stops listening.
+pub(crate) struct ResizeListener {
+    source: ResizeSource,
+    redraw: Rc<Redraw>,
+}
+
+enum ResizeSource {
+    Observer {
+        observer: ResizeObserver,
+        _callback: Closure<dyn FnMut()>,
+    },
+    Window {
+        _listener: EventListener,
+    },
+}
+
+impl ResizeListener {
+    /// Requests a redraw in the next animation frame, shared with those for resizing, so that
+    /// any number of requests before then draw only once. Does nothing once the listener is
+    /// dropped.
+    pub(crate) fn scheduler(&self) -> Rc<dyn Fn()> {
+        let redraw = Rc::downgrade(&self.redraw);
+        Rc::new(move || {
+            if let Some(redraw) = redraw.upgrade() {
+                redraw.schedule();
+            }
+        })
+    }
+}
+
+impl Drop for ResizeListener {
+    fn drop(&mut self) {
+        if let ResizeSource::Observer { observer, .. } = &self.source {
+            observer.disconnect();
+        }
+    }
+}
+
+/// Coalesces redraw requests into at most one per animation frame.
+struct Redraw {
+    callback: Box<dyn Fn()>,
+    frame: RefCell<Option<AnimationFrame>>,
+    pixel_ratio: RefCell<Option<PixelRatioWatch>>,
+}
+
+/// Fires once the device pixel ratio stops matching `ratio`.
+struct PixelRatioWatch {
+    ratio: f64,
+    _listener: EventListener,
+}
+
+impl Redraw {
+    fn schedule(self: &Rc<Self>) {
+        if self.frame.borrow().is_some() {
+            // A redraw is already queued for the next frame
+            return;
+        }
+
+        let redraw = Rc::downgrade(self);
+        *self.frame.borrow_mut() = Some(request_animation_frame(move |_timestamp| {
+            if let Some(redraw) = redraw.upgrade() {
+                redraw.frame.borrow_mut().take();
+                redraw.watch_pixel_ratio();
+                (redraw.callback)();
+            }
+        }));
+    }
+
+    /// `matchMedia` can only tell whether the ratio matches a given value, so the query is
+    /// re-created for the new ratio every time it changes.
+    fn watch_pixel_ratio(self: &Rc<Self>) {
+        let Some(window) = window() else {
+            return;
+        };
+        let ratio = window.device_pixel_ratio();
+
+        if matches!(&*self.pixel_ratio.borrow(), Some(watch) if watch.ratio == ratio) {
+            return;
+        }
+
+        let query = format!("(resolution: {}dppx)", ratio);
+        let Some(media_query) = window.match_media(&query).ok().flatten() else {
+            return;
+        };
+
+        let redraw: Weak<Self> = Rc::downgrade(self);
+        let listener = EventListener::new(&media_query, "change", move |_event| {
+            if let Some(redraw) = redraw.upgrade() {
+                redraw.schedule();
+            }
+        });
+
+        *self.pixel_ratio.borrow_mut() = Some(PixelRatioWatch {
+            ratio,
+            _listener: listener,
+        });
+    }
+}
+
+/// Calls `callback` whenever the parent of `canvas` changes size or the device pixel ratio
+/// changes, at most once per animation frame.
+///
+/// The container is watched with a `ResizeObserver`, so collapsing sidebars, tabs and grid
+/// cells trigger a redraw too. Browsers without `ResizeObserver` fall back to the window's
+/// `resize` event.
+pub(crate) fn listen_for_resize(
+    canvas: &HtmlCanvasElement,
+    callback: impl Fn() + 'static,
+) -> ResizeListener {
+    listen(canvas.parent_element(), callback)
+}
+
+/// Calls `callback` whenever `element` changes size, like [`listen_for_resize`] does for a
+/// canvas' container.
+pub(crate) fn listen_for_element_resize(
+    element: &Element,
+    callback: impl Fn() + 'static,
+) -> ResizeListener {
+    listen(Some(element.clone()), callback)
+}
+
+fn listen(target: Option<Element>, callback: impl Fn() + 'static) -> ResizeListener {
+    let redraw = Rc::new(Redraw {
+        callback: Box::new(callback),
+        frame: RefCell::new(None),
+        pixel_ratio: RefCell::new(None),
+    });
+    redraw.watch_pixel_ratio();
+
+    let source = target
+        .filter(|_| supports_resize_observer())
+        .and_then(|parent| {
+            let redraw = redraw.clone();
+            let callback = Closure::<dyn FnMut()>::new(move || redraw.schedule());
+            let observer = ResizeObserver::new(callback.as_ref().unchecked_ref()).ok()?;
+            observer.observe(&parent);
+
+            Some(ResizeSource::Observer {
+                observer,
+                _callback: callback,
+            })
+        })
+        .unwrap_or_else(|| {
+            let redraw = redraw.clone();
+            let listener = EventListener::new(&window().unwrap(), "resize", move |_event| {
+                redraw.schedule()
+            });
+
+            ResizeSource::Window {
+                _listener: listener,
+            }
+        });
+
+    ResizeListener { source, redraw }
+}
+
+/// Sizes the canvas' backing store for the current device pixel ratio and resets the context's
+/// transform so that drawing code can work in CSS pixels.
+///
+/// The transform is set rather than multiplied, so calling this repeatedly never compounds the
+/// scale.
+pub(crate) fn prepare_canvas(
+    canvas: &HtmlCanvasElement,
+    context: &CanvasRenderingContext2d,
+    width: f64,
+    height: f64,
+) {
+    let device_pixel_ratio = window().map_or(1.0, |window| window.device_pixel_ratio());
+
+    let backing_width = (width * device_pixel_ratio).round() as u32;
+    let backing_height = (height * device_pixel_ratio).round() as u32;
+    if canvas.width() != backing_width || canvas.height() != backing_height {
+        canvas.set_width(backing_width);
+        canvas.set_height(backing_height);
+    }
+
+    let _ignored_result =
+        context.set_transform(device_pixel_ratio, 0.0, 0.0, device_pixel_ratio, 0.0, 0.0);
+}
+
+fn supports_resize_observer() -> bool {
+    window().is_some_and(|window| {
+        Reflect::has(&window, &JsValue::from_str("ResizeObserver")).unwrap_or(false)
+    })
+}
@@ -0,0 +1,813 @@
+use std::f64::consts::PI;
+
+use crate::charts::canvas::Canvas;
+use crate::charts::interaction::PointIndex;
+use crate::charts::pattern::Pattern;
+use crate::charts::text::{estimated_text_width, font_size};
+
+/// Everything a chart draws, laid out in CSS pixels but not drawn yet.
+///
+/// Charts turn their props and size into a scene and then [`paint`](Scene::paint) it, so the
+/// same geometry is available for hit testing, tooltips and custom overlays. Get one through
+/// [`Chart::scene`](crate::canvas::Chart::scene):
+///
+///```
+/// # use visualize_yew::canvas::Chart;
+/// # use visualize_yew::charts::bar_chart::bar_chart::{BarChartProps, DataPoint};
+/// # use visualize_yew::interaction::PointIndex;
+/// # use visualize_yew::theme::Theme;
+/// let props = BarChartProps {
+///     data: vec![DataPoint::new("A", 10), DataPoint::new("B", 20)],
+///     config: Default::default(),
+///     ..Default::default()
+/// };
+///
+/// let scene = props.scene(500.0, 300.0, &Theme::light());
+/// let (x, y) = scene.anchor(PointIndex::new(0, 1)).unwrap();
+/// assert_eq!(scene.hit_test(x, y + 1.0), Some(PointIndex::new(0, 1)));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Scene {
+    pub width: f64,
+    pub height: f64,
+    /// Painted over the whole chart after clearing it.
+    pub background: String,
+    /// In the order they are painted, so later items are on top.
+    pub items: Vec<SceneItem>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SceneItem {
+    Line(Line),
+    Rect(Rect),
+    Sector(Sector),
+    Polyline(Polyline),
+    Path(Path),
+    Band(Band),
+    Circle(Circle),
+    Label(Label),
+    /// Items painted together, clipped to `clip` when it is set.
+    Group {
+        clip: Option<Bounds>,
+        items: Vec<SceneItem>,
+    },
+}
+
+/// An axis-aligned rectangle.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Bounds {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Stroke {
+    pub color: String,
+    pub width: f64,
+}
+
+/// A pattern drawn over a fill, see [`Pattern`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct PatternFill {
+    pub pattern: Pattern,
+    pub ink: String,
+}
+
+/// A straight line, such as an axis or a grid line.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Line {
+    pub from: (f64, f64),
+    pub to: (f64, f64),
+    pub stroke: Stroke,
+    /// Lengths drawn and skipped in turn along the line, or empty for a solid line.
+    pub dash: Vec<f64>,
+}
+
+/// A filled rectangle, such as a bar.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rect {
+    pub bounds: Bounds,
+    pub fill: String,
+    pub pattern: Option<PatternFill>,
+    pub outline: Option<Stroke>,
+    /// The data point the rectangle shows, if any.
+    pub point: Option<PointIndex>,
+}
+
+/// A pie slice, or a segment of a ring when `inner_radius` isn't zero. Angles are in radians,
+/// clockwise from the positive x-axis.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Sector {
+    pub center: (f64, f64),
+    pub radius: f64,
+    pub inner_radius: f64,
+    pub start_angle: f64,
+    pub end_angle: f64,
+    pub fill: String,
+    pub pattern: Option<PatternFill>,
+    pub outline: Option<Stroke>,
+    pub point: Option<PointIndex>,
+}
+
+/// A line through a series' points, optionally filled down to a baseline.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Polyline {
+    pub points: Vec<(f64, f64)>,
+    /// The two Bézier control points of the curve into each point after the first. Empty for
+    /// straight segments.
+    pub controls: Vec<[(f64, f64); 2]>,
+    pub stroke: Stroke,
+    pub area: Option<Area>,
+    /// The series the line shows.
+    pub series: usize,
+    /// The index of the data point behind each of `points`, when the line only shows some of
+    /// them. `None` when every point is drawn.
+    pub indices: Option<Vec<usize>>,
+}
+
+/// The area between a [`Polyline`] and a horizontal baseline.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Area {
+    pub baseline: f64,
+    pub fill: String,
+    /// How opaque the fill is, from 0 to 1. Patterns are drawn over it fully opaque.
+    pub opacity: f64,
+    pub pattern: Option<PatternFill>,
+}
+
+/// Straight segments through `points` that don't stand for data points of their own, such as
+/// a trend line.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Path {
+    pub points: Vec<(f64, f64)>,
+    pub stroke: Stroke,
+    /// Lengths drawn and skipped in turn along the path, or empty for a solid one.
+    pub dash: Vec<f64>,
+}
+
+/// The area between two lines, such as a confidence interval. `upper` and `lower` are joined
+/// at their ends, so both run in the same direction.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Band {
+    pub upper: Vec<(f64, f64)>,
+    pub lower: Vec<(f64, f64)>,
+    pub fill: String,
+    /// How opaque the fill is, from 0 to 1. Applied separately, so `fill` can be any CSS color.
+    pub opacity: f64,
+}
+
+/// A dot or a ring, such as a data point marker.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Circle {
+    pub center: (f64, f64),
+    pub radius: f64,
+    pub fill: Option<String>,
+    pub outline: Option<Stroke>,
+    pub point: Option<PointIndex>,
+}
+
+/// Text anchored at `position` the way a canvas anchors it, e.g. `align: "center"` puts the
+/// middle of the text at `position`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Label {
+    pub text: String,
+    pub position: (f64, f64),
+    /// A CSS font shorthand, e.g. `"bold 12px Arial"`.
+    pub font: String,
+    pub color: String,
+    pub align: String,
+    pub baseline: String,
+    /// Clockwise, in radians, around `position`.
+    pub rotation: f64,
+}
+
+impl Bounds {
+    pub fn contains(&self, x: f64, y: f64) -> bool {
+        x >= self.x && x <= self.x + self.width && y >= self.y && y <= self.y + self.height
+    }
+}
+
+impl SceneItem {
+    /// Moves the item, and everything in it, by `dx` to the right and `dy` down.
+    pub fn translate(&mut self, dx: f64, dy: f64) {
+        let shift = |point: &mut (f64, f64)| {
+            point.0 += dx;
+            point.1 += dy;
+        };
+
+        match self {
+            SceneItem::Line(line) => {
+                shift(&mut line.from);
+                shift(&mut line.to);
+            }
+            SceneItem::Rect(rect) => {
+                rect.bounds.x += dx;
+                rect.bounds.y += dy;
+            }
+            SceneItem::Sector(sector) => shift(&mut sector.center),
+            SceneItem::Polyline(line) => {
+                line.points.iter_mut().for_each(shift);
+                line.controls.iter_mut().flatten().for_each(shift);
+                if let Some(area) = &mut line.area {
+                    area.baseline += dy;
+                }
+            }
+            SceneItem::Path(path) => path.points.iter_mut().for_each(shift),
+            SceneItem::Band(band) => {
+                band.upper.iter_mut().for_each(shift);
+                band.lower.iter_mut().for_each(shift);
+            }
+            SceneItem::Circle(circle) => shift(&mut circle.center),
+            SceneItem::Label(label) => shift(&mut label.position),
+            SceneItem::Group { clip, items } => {
+                if let Some(clip) = clip {
+                    clip.x += dx;
+                    clip.y += dy;
+                }
+                for item in items {
+                    item.translate(dx, dy);
+                }
+            }
+        }
+    }
+}
+
+impl Label {
+    /// The area the label covers. Widths are estimated from the font size, since there are no
+    /// font metrics to go by outside of a canvas.
+    pub fn bounds(&self) -> Bounds {
+        let size = font_size(&self.font);
+        let width = estimated_text_width(&self.font, &self.text);
+
+        let left = match self.align.as_str() {
+            "center" => -width / 2.0,
+            "right" | "end" => -width,
+            _ => 0.0,
+        };
+        let top = match self.baseline.as_str() {
+            "top" | "hanging" => 0.0,
+            "middle" => -size / 2.0,
+            "bottom" | "ideographic" => -size,
+            // Roughly the ascent of most fonts
+            _ => -size * 0.8,
+        };
+
+        let (sin, cos) = self.rotation.sin_cos();
+        let corners = [
+            (left, top),
+            (left + width, top),
+            (left, top + size),
+            (left + width, top + size),
+        ]
+        .map(|(x, y)| {
+            (
+                self.position.0 + x * cos - y * sin,
+                self.position.1 + x * sin + y * cos,
+            )
+        });
+
+        let min_x = corners.iter().map(|c| c.0).fold(f64::INFINITY, f64::min);
+        let max_x = corners
+            .iter()
+            .map(|c| c.0)
+            .fold(f64::NEG_INFINITY, f64::max);
+        let min_y = corners.iter().map(|c| c.1).fold(f64::INFINITY, f64::min);
+        let max_y = corners
+            .iter()
+            .map(|c| c.1)
+            .fold(f64::NEG_INFINITY, f64::max);
+        Bounds {
+            x: min_x,
+            y: min_y,
+            width: max_x - min_x,
+            height: max_y - min_y,
+        }
+    }
+}
+
+impl Sector {
+    fn contains(&self, x: f64, y: f64) -> bool {
+        let (dx, dy) = (x - self.center.0, y - self.center.1);
+        let distance = dx.hypot(dy);
+        if distance < self.inner_radius || distance > self.radius {
+            return false;
+        }
+
+        let angle = (dy.atan2(dx) - self.start_angle).rem_euclid(2.0 * PI);
+        angle <= self.end_angle - self.start_angle
+    }
+
+    /// The middle of a ring segment, or two thirds of the way out along a pie slice.
+    fn anchor(&self) -> (f64, f64) {
+        let mid_angle = (self.start_angle + self.end_angle) / 2.0;
+        let distance = if self.inner_radius > 0.0 {
+            (self.radius + self.inner_radius) / 2.0
+        } else {
+            self.radius * 2.0 / 3.0
+        };
+
+        (
+            self.center.0 + distance * mid_angle.cos(),
+            self.center.1 + distance * mid_angle.sin(),
+        )
+    }
+}
+
+impl Scene {
+    /// Every item, with the contents of groups in place of the groups.
+    pub fn flatten(&self) -> Vec<&SceneItem> {
+        fn flatten_into<'a>(items: &'a [SceneItem], flat: &mut Vec<&'a SceneItem>) {
+            for item in items {
+                match item {
+                    SceneItem::Group { items, .. } => flatten_into(items, flat),
+                    item => flat.push(item),
+                }
+            }
+        }
+
+        let mut flat = Vec::new();
+        flatten_into(&self.items, &mut flat);
+        flat
+    }
+
+    /// Where a tooltip for `point` points to: the middle of a bar's top edge, inside a slice
+    /// or segment, or the point itself on a line.
+    pub fn anchor(&self, point: PointIndex) -> Option<(f64, f64)> {
+        self.flatten().into_iter().find_map(|item| match item {
+            SceneItem::Rect(rect) if rect.point == Some(point) => {
+                Some((rect.bounds.x + rect.bounds.width / 2.0, rect.bounds.y))
+            }
+            SceneItem::Sector(sector) if sector.point == Some(point) => Some(sector.anchor()),
+            SceneItem::Polyline(line) if line.series == point.series => match &line.indices {
+                Some(indices) => indices
+                    .binary_search(&point.index)
+                    .ok()
+                    .map(|drawn| line.points[drawn]),
+                None => line.points.get(point.index).copied(),
+            },
+            SceneItem::Circle(circle) if circle.point == Some(point) => Some(circle.center),
+            _ => None,
+        })
+    }
+
+    /// The data point drawn at `(x, y)`, if any. Markers on lines count when hovered within a
+    /// few pixels, so that they are easy to hit.
+    pub fn hit_test(&self, x: f64, y: f64) -> Option<PointIndex> {
+        const MARKER_TOLERANCE: f64 = 4.0;
+
+        self.flatten()
+            .into_iter()
+            .rev()
+            .find_map(|item| match item {
+                SceneItem::Rect(rect) if rect.bounds.contains(x, y) => rect.point,
+                SceneItem::Sector(sector) if sector.contains(x, y) => sector.point,
+                SceneItem::Circle(circle) => {
+                    let distance = (x - circle.center.0).hypot(y - circle.center.1);
+                    if distance <= circle.radius + MARKER_TOLERANCE {
+                        circle.point
+                    } else {
+                        None
+                    }
+                }
+                _ => None,
+            })
+    }
+
+    /// Clears the chart's area and draws the scene onto `canvas`.
+    pub fn paint(&self, canvas: &dyn Canvas) {
+        let mut painter = Painter::new(canvas);
+
+        canvas.clear_rect(0.0, 0.0, self.width, self.height);
+        painter.set_fill_style(&self.background);
+        canvas.fill_rect(0.0, 0.0, self.width, self.height);
+
+        for item in &self.items {
+            painter.paint(item);
+        }
+    }
+}
+
+/// Paints scene items, skipping style changes that wouldn't change anything.
+struct Painter<'a> {
+    canvas: &'a dyn Canvas,
+    fill: Option<String>,
+    stroke: Option<String>,
+    line_width: Option<f64>,
+    font: Option<String>,
+    align: Option<String>,
+    baseline: Option<String>,
+}
+
+impl<'a> Painter<'a> {
+    fn new(canvas: &'a dyn Canvas) -> Self {
+        Self {
+            canvas,
+            fill: None,
+            stroke: None,
+            line_width: None,
+            font: None,
+            align: None,
+            baseline: None,
+        }
+    }
+
+    fn set_fill_style(&mut self, color: &str) {
+        if self.fill.as_deref() != Some(color) {
+            self.canvas.set_fill_style(color);
+            self.fill = Some(color.to_string());
+        }
+    }
+
+    fn set_stroke(&mut self, stroke: &Stroke) {
+        if self.stroke.as_deref() != Some(stroke.color.as_str()) {
+            self.canvas.set_stroke_style(&stroke.color);
+            self.stroke = Some(stroke.color.clone());
+        }
+        if self.line_width != Some(stroke.width) {
+            self.canvas.set_line_width(stroke.width);
+            self.line_width = Some(stroke.width);
+        }
+    }
+
+    fn set_text_style(&mut self, label: &Label) {
+        self.set_fill_style(&label.color);
+        if self.font.as_deref() != Some(label.font.as_str()) {
+            self.canvas.set_font(&label.font);
+            self.font = Some(label.font.clone());
+        }
+        if self.align.as_deref() != Some(label.align.as_str()) {
+            self.canvas.set_text_align(&label.align);
+            self.align = Some(label.align.clone());
+        }
+        if self.baseline.as_deref() != Some(label.baseline.as_str()) {
+            self.canvas.set_text_baseline(&label.baseline);
+            self.baseline = Some(label.baseline.clone());
+        }
+    }
+
+    /// Fills the current path with `color`, `opacity` of the way opaque.
+    fn fill_translucent(&mut self, color: &str, opacity: f64) {
+        self.set_fill_style(color);
+        if opacity < 1.0 {
+            self.canvas.set_global_alpha(opacity);
+            self.canvas.fill();
+            self.canvas.set_global_alpha(1.0);
+        } else {
+            self.canvas.fill();
+        }
+    }
+
+    fn fill_pattern(&self, pattern: &Option<PatternFill>) {
+        if let Some(pattern) = pattern {
+            self.canvas.fill_pattern(pattern.pattern, &pattern.ink);
+        }
+    }
+
+    fn paint(&mut self, item: &SceneItem) {
+        let canvas = self.canvas;
+
+        match item {
+            SceneItem::Line(line) => {
+                self.set_stroke(&line.stroke);
+                canvas.begin_path();
+                canvas.move_to(line.from.0, line.from.1);
+                canvas.line_to(line.to.0, line.to.1);
+                if line.dash.is_empty() {
+                    canvas.stroke();
+                } else {
+                    canvas.set_line_dash(&line.dash);
+                    canvas.stroke();
+                    canvas.set_line_dash(&[]);
+                }
+            }
+            SceneItem::Rect(rect) => {
+                let Bounds {
+                    x,
+                    y,
+                    width,
+                    height,
+                } = rect.bounds;
+                self.set_fill_style(&rect.fill);
+                canvas.fill_rect(x, y, width, height);
+                if rect.pattern.is_some() {
+                    canvas.begin_path();
+                    canvas.rect(x, y, width, height);
+                    self.fill_pattern(&rect.pattern);
+                }
+                if let Some(outline) = &rect.outline {
+                    self.set_stroke(outline);
+                    canvas.stroke_rect(x, y, width, height);
+                }
+            }
+            SceneItem::Sector(sector) => {
+                let (x, y) = sector.center;
+                canvas.begin_path();
+                if sector.inner_radius > 0.0 {
+                    canvas.arc(x, y, sector.radius, sector.start_angle, sector.end_angle);
+                    canvas.arc_anticlockwise(
+                        x,
+                        y,
+                        sector.inner_radius,
+                        sector.end_angle,
+                        sector.start_angle,
+                    );
+                } else {
+                    canvas.move_to(x, y);
+                    canvas.arc(x, y, sector.radius, sector.start_angle, sector.end_angle);
+                }
+                canvas.close_path();
+
+                self.set_fill_style(&sector.fill);
+                canvas.fill();
+                self.fill_pattern(&sector.pattern);
+                if let Some(outline) = &sector.outline {
+                    self.set_stroke(outline);
+                    canvas.stroke();
+                }
+            }
+            SceneItem::Polyline(line) => {
+                let Some(&(first_x, first_y)) = line.points.first() else {
+                    return;
+                };
+
+                self.set_stroke(&line.stroke);
+                canvas.begin_path();
+                canvas.move_to(first_x, first_y);
+                for (i, &(x, y)) in line.points.iter().enumerate().skip(1) {
+                    match line.controls.get(i - 1) {
+                        Some(&[(cp1x, cp1y), (cp2x, cp2y)]) => {
+                            canvas.bezier_curve_to(cp1x, cp1y, cp2x, cp2y, x, y)
+                        }
+                        None => canvas.line_to(x, y),
+                    }
+                }
+                canvas.stroke();
+
+                if let Some(area) = &line.area {
+                    let last_x = line.points.last().map_or(first_x, |point| point.0);
+                    canvas.line_to(last_x, area.baseline);
+                    canvas.line_to(first_x, area.baseline);
+                    canvas.close_path();
+
+                    self.fill_translucent(&area.fill, area.opacity);
+                    self.fill_pattern(&area.pattern);
+                }
+            }
+            SceneItem::Path(path) => {
+                let Some(&(first_x, first_y)) = path.points.first() else {
+                    return;
+                };
+
+                self.set_stroke(&path.stroke);
+                canvas.begin_path();
+                canvas.move_to(first_x, first_y);
+                for &(x, y) in &path.points[1..] {
+                    canvas.line_to(x, y);
+                }
+                if path.dash.is_empty() {
+                    canvas.stroke();
+                } else {
+                    canvas.set_line_dash(&path.dash);
+                    canvas.stroke();
+                    canvas.set_line_dash(&[]);
+                }
+            }
+            SceneItem::Band(band) => {
+                let Some(&(first_x, first_y)) = band.upper.first() else {
+                    return;
+                };
+
+                canvas.begin_path();
+                canvas.move_to(first_x, first_y);
+                for &(x, y) in band.upper[1..].iter().chain(band.lower.iter().rev()) {
+                    canvas.line_to(x, y);
+                }
+                canvas.close_path();
+                self.fill_translucent(&band.fill, band.opacity);
+            }
+            SceneItem::Circle(circle) => {
+                canvas.begin_path();
+                canvas.arc(
+                    circle.center.0,
+                    circle.center.1,
+                    circle.radius,
+                    0.0,
+                    2.0 * PI,
+                );
+                if let Some(fill) = &circle.fill {
+                    self.set_fill_style(fill);
+                    canvas.fill();
+                }
+                if let Some(outline) = &circle.outline {
+                    self.set_stroke(outline);
+                    canvas.stroke();
+                }
+            }
+            SceneItem::Label(label) => {
+                self.set_text_style(label);
+                let (x, y) = label.position;
+                if label.rotation == 0.0 {
+                    canvas.fill_text(&label.text, x, y);
+                } else {
+                    canvas.save();
+                    canvas.translate(x, y);
+                    canvas.rotate(label.rotation);
+                    canvas.fill_text(&label.text, 0.0, 0.0);
+                    canvas.restore();
+                }
+            }
+            SceneItem::Group { clip, items } => {
+                canvas.save();
+                if let Some(clip) = clip {
+                    canvas.begin_path();
+                    canvas.rect(clip.x, clip.y, clip.width, clip.height);
+                    canvas.clip();
+                }
+
+                // Styles set inside the group are undone when it is restored
+                let mut painter = Painter::new(canvas);
+                painter.fill.clone_from(&self.fill);
+                painter.stroke.clone_from(&self.stroke);
+                painter.line_width = self.line_width;
+                painter.font.clone_from(&self.font);
+                painter.align.clone_from(&self.align);
+                painter.baseline.clone_from(&self.baseline);
+                for item in items {
+                    painter.paint(item);
+                }
+
+                canvas.restore();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::charts::recording::{DrawCommand, RecordingCanvas};
+
+    fn label(align: &str, baseline: &str, rotation: f64) -> Label {
+        Label {
+            text: "abcde".to_string(),
+            position: (100.0, 50.0),
+            font: "10px sans-serif".to_string(),
+            color: "black".to_string(),
+            align: align.to_string(),
+            baseline: baseline.to_string(),
+            rotation,
+        }
+    }
+
+    #[test]
+    fn label_bounds_follow_alignment_and_rotation() {
+        assert_eq!(
+            label("center", "middle", 0.0).bounds(),
+            Bounds {
+                x: 85.0,
+                y: 45.0,
+                width: 30.0,
+                height: 10.0
+            }
+        );
+        assert_eq!(label("right", "top", 0.0).bounds().x, 70.0);
+
+        let rotated = label("center", "middle", -PI / 2.0).bounds();
+        assert!((rotated.width - 10.0).abs() < 1e-9);
+        assert!((rotated.height - 30.0).abs() < 1e-9);
+        assert!((rotated.y - 35.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn hit_tests_sectors_and_markers() {
+        let scene = Scene {
+            width: 100.0,
+            height: 100.0,
+            background: "white".to_string(),
+            items: vec![
+                SceneItem::Sector(Sector {
+                    center: (50.0, 50.0),
+                    radius: 40.0,
+                    inner_radius: 20.0,
+                    start_angle: -PI / 2.0,
+                    end_angle: 0.0,
+                    fill: "red".to_string(),
+                    pattern: None,
+                    outline: None,
+                    point: Some(PointIndex::new(0, 0)),
+                }),
+                SceneItem::Circle(Circle {
+                    center: (10.0, 90.0),
+                    radius: 3.0,
+                    fill: Some("blue".to_string()),
+                    outline: None,
+                    point: Some(PointIndex::new(1, 4)),
+                }),
+            ],
+        };
+
+        // Top right quarter of the ring
+        assert_eq!(scene.hit_test(70.0, 30.0), Some(PointIndex::new(0, 0)));
+        assert_eq!(scene.hit_test(55.0, 45.0), None, "inside the hole");
+        assert_eq!(scene.hit_test(30.0, 30.0), None, "another quarter");
+        assert_eq!(scene.hit_test(14.0, 92.0), Some(PointIndex::new(1, 4)));
+
+        let (x, y) = scene.anchor(PointIndex::new(0, 0)).unwrap();
+        assert!((x - (50.0 + 30.0 * (PI / 4.0).cos())).abs() < 1e-9);
+        assert!((y - (50.0 - 30.0 * (PI / 4.0).sin())).abs() < 1e-9);
+    }
+
+    #[test]
+    fn paints_groups_with_clips_and_skips_repeated_styles() {
+        let line = |y: f64| {
+            SceneItem::Line(Line {
+                from: (0.0, y),
+                to: (10.0, y),
+                stroke: Stroke {
+                    color: "gray".to_string(),
+                    width: 1.0,
+                },
+                dash: Vec::new(),
+            })
+        };
+        let scene = Scene {
+            width: 10.0,
+            height: 10.0,
+            background: "white".to_string(),
+            items: vec![
+                line(1.0),
+                SceneItem::Group {
+                    clip: Some(Bounds {
+                        x: 0.0,
+                        y: 0.0,
+                        width: 5.0,
+                        height: 10.0,
+                    }),
+                    items: vec![line(2.0)],
+                },
+            ],
+        };
+
+        let canvas = RecordingCanvas::new();
+        scene.paint(&canvas);
+        let commands = canvas.commands();
+
+        assert_eq!(
+            commands
+                .iter()
+                .filter(|command| matches!(command, DrawCommand::SetStrokeStyle(_)))
+                .count(),
+            1
+        );
+        assert!(commands.contains(&DrawCommand::Clip));
+        assert_eq!(commands.last(), Some(&DrawCommand::Restore));
+    }
+
+    #[test]
+    fn bands_run_out_along_the_upper_line_and_back_along_the_lower_one() {
+        let scene = Scene {
+            width: 10.0,
+            height: 10.0,
+            background: "white".to_string(),
+            items: vec![SceneItem::Band(Band {
+                upper: vec![(0.0, 2.0), (5.0, 1.0), (10.0, 2.0)],
+                lower: vec![(0.0, 6.0), (5.0, 5.0), (10.0, 6.0)],
+                fill: "#36a2eb".to_string(),
+                opacity: 0.2,
+            })],
+        };
+
+        let canvas = RecordingCanvas::new();
+        scene.paint(&canvas);
+        let commands = canvas.commands();
+        let start = commands
+            .iter()
+            .position(|command| *command == DrawCommand::MoveTo { x: 0.0, y: 2.0 })
+            .unwrap();
+
+        assert_eq!(
+            commands[start + 1..start + 6],
+            [
+                DrawCommand::LineTo { x: 5.0, y: 1.0 },
+                DrawCommand::LineTo { x: 10.0, y: 2.0 },
+                DrawCommand::LineTo { x: 10.0, y: 6.0 },
+                DrawCommand::LineTo { x: 5.0, y: 5.0 },
+                DrawCommand::LineTo { x: 0.0, y: 6.0 },
+            ]
+        );
+        assert_eq!(commands[start + 6], DrawCommand::ClosePath);
+
+        // Filled see-through, and everything after it opaque again
+        assert_eq!(
+            commands[start + 7..start + 11],
+            [
+                DrawCommand::SetFillStyle("#36a2eb".to_string()),
+                DrawCommand::SetGlobalAlpha(0.2),
+                DrawCommand::Fill,
+                DrawCommand::SetGlobalAlpha(1.0),
+            ]
+        );
+    }
+}
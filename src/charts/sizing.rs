@@ -0,0 +1,186 @@
+use web_sys::HtmlCanvasElement;
+
+/// How a chart picks its width and height.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum SizeMode {
+    /// Take the full width of the container and the chart's own default aspect ratio.
+    #[default]
+    Auto,
+    /// Always render at exactly this size, in CSS pixels.
+    Fixed { width: u32, height: u32 },
+    /// Take the full width of the container and keep the `width:height` ratio,
+    /// e.g. `AspectRatio { width: 16, height: 9 }`.
+    AspectRatio { width: u32, height: u32 },
+    /// Take both the full width and the full height of the container.
+    ///
+    /// The container needs a definite height (e.g. from a fixed `height`, flex or grid layout),
+    /// otherwise the chart collapses to its `min_height`.
+    FillParent,
+}
+
+/// Sizing options shared by every chart config.
+///
+///```
+/// # use visualize_yew::sizing::ChartSize;
+/// let mut size = ChartSize::aspect_ratio(16, 9);
+/// size.max_height = Some(400);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct ChartSize {
+    pub mode: SizeMode,
+    pub min_width: Option<u32>,
+    pub max_width: Option<u32>,
+    pub min_height: Option<u32>,
+    pub max_height: Option<u32>,
+}
+
+impl ChartSize {
+    pub fn fixed(width: u32, height: u32) -> Self {
+        Self::with_mode(SizeMode::Fixed { width, height })
+    }
+
+    pub fn aspect_ratio(width: u32, height: u32) -> Self {
+        Self::with_mode(SizeMode::AspectRatio { width, height })
+    }
+
+    pub fn fill_parent() -> Self {
+        Self::with_mode(SizeMode::FillParent)
+    }
+
+    fn with_mode(mode: SizeMode) -> Self {
+        Self {
+            mode,
+            ..Default::default()
+        }
+    }
+
+    /// Computes the chart's size in CSS pixels from the space its container offers.
+    ///
+    /// `default_ratio` is the height-to-width ratio used by [`SizeMode::Auto`]. The min/max
+    /// constraints are applied last, so they win over the aspect ratio.
+    pub fn resolve(
+        &self,
+        available_width: f64,
+        available_height: f64,
+        default_ratio: f64,
+    ) -> (f64, f64) {
+        let (width, height) = match self.mode {
+            SizeMode::Auto => (available_width, available_width * default_ratio),
+            SizeMode::Fixed { width, height } => (width as f64, height as f64),
+            SizeMode::AspectRatio { width, height } => {
+                let ratio = if width == 0 {
+                    default_ratio
+                } else {
+                    height as f64 / width as f64
+                };
+                let width = clamp(available_width, self.min_width, self.max_width);
+                (width, width * ratio)
+            }
+            SizeMode::FillParent => (available_width, available_height),
+        };
+
+        (
+            clamp(width, self.min_width, self.max_width),
+            clamp(height, self.min_height, self.max_height),
+        )
+    }
+
+    /// Extra styles for the element wrapping the canvas, so that it can grow into its container.
+    pub(crate) fn container_style(&self) -> &'static str {
+        match self.mode {
+            SizeMode::FillParent => "height: 100%;",
+            _ => "",
+        }
+    }
+}
+
+fn clamp(value: f64, min: Option<u32>, max: Option<u32>) -> f64 {
+    let value = match max {
+        Some(max) => value.min(max as f64),
+        None => value,
+    };
+
+    match min {
+        Some(min) => value.max(min as f64),
+        None => value,
+    }
+}
+
+/// Measures the space available to `canvas` inside its parent, resolves `size` against it
+/// and sets the canvas' CSS size accordingly. Returns the resolved size in CSS pixels.
+pub(crate) fn fit_canvas(
+    canvas: &HtmlCanvasElement,
+    size: &ChartSize,
+    default_ratio: f64,
+) -> (f64, f64) {
+    let parent = canvas.parent_element().unwrap();
+    let available_width = parent.client_width() as f64;
+
+    // Leave room for anything rendered above the canvas in the same container, e.g. the legend
+    let offset = canvas.get_bounding_client_rect().top() - parent.get_bounding_client_rect().top();
+    // and for a legend below it
+    let mut below = 0.0;
+    let mut sibling = canvas.next_element_sibling();
+    while let Some(element) = sibling {
+        if element.has_attribute("data-chart-legend") {
+            below += element.get_bounding_client_rect().height();
+        }
+        sibling = element.next_element_sibling();
+    }
+    let available_height = (parent.client_height() as f64 - offset - below).max(0.0);
+
+    let (width, height) = size.resolve(available_width, available_height, default_ratio);
+
+    let style = canvas.style();
+    let _ignored_result = style.set_property("width", &format!("{}px", width));
+    let _ignored_result = style.set_property("height", &format!("{}px", height));
+
+    (width, height)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn auto_uses_default_ratio() {
+        let size = ChartSize::default();
+
+        assert_eq!(size.resolve(500.0, 100.0, 0.6), (500.0, 300.0));
+    }
+
+    #[test]
+    fn fixed_ignores_container() {
+        let size = ChartSize::fixed(320, 200);
+
+        assert_eq!(size.resolve(1000.0, 1000.0, 0.6), (320.0, 200.0));
+    }
+
+    #[test]
+    fn aspect_ratio_follows_width() {
+        let size = ChartSize::aspect_ratio(16, 9);
+
+        assert_eq!(size.resolve(800.0, 100.0, 0.6), (800.0, 450.0));
+    }
+
+    #[test]
+    fn fill_parent_uses_both_dimensions() {
+        let size = ChartSize::fill_parent();
+
+        assert_eq!(size.resolve(640.0, 480.0, 0.6), (640.0, 480.0));
+    }
+
+    #[test]
+    fn constraints_are_applied_last() {
+        let mut size = ChartSize::aspect_ratio(2, 1);
+        size.max_width = Some(600);
+        size.min_height = Some(400);
+
+        assert_eq!(size.resolve(1000.0, 0.0, 0.6), (600.0, 400.0));
+
+        let mut size = ChartSize::fill_parent();
+        size.min_height = Some(150);
+
+        assert_eq!(size.resolve(300.0, 0.0, 0.6), (300.0, 150.0));
+    }
+}
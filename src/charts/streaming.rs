@@ -0,0 +1,526 @@
+use gloo::timers::callback::Interval;
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::fmt;
+use std::rc::Rc;
+use std::time::Duration;
+use web_sys::js_sys::Date;
+use yew::prelude::*;
+
+use crate::charts::animation::{animate, Animation, AnimationConfig, AnimationFrame, Easing};
+
+/// Options for a [`LineStream`].
+///
+///```
+/// # use std::time::Duration;
+/// # use visualize_yew::streaming::StreamOptions;
+/// let options = StreamOptions {
+///     max_points: Some(60),
+///     max_age: Some(Duration::from_secs(60)),
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct StreamOptions {
+    /// Keep only the last this many points. Also decides how many points fit across the
+    /// x-axis, so the lines fill the chart from the left until the window is full and scroll
+    /// from then on.
+    pub max_points: Option<usize>,
+    /// Drop points older than this, measured from the newest point.
+    pub max_age: Option<Duration>,
+    /// How far the data has to drop below the top of the y-axis before the axis shrinks, as
+    /// a fraction of it. The axis grows as soon as a value doesn't fit, so `0.0` rescales on
+    /// every point and larger values keep the grid lines still.
+    pub hysteresis: f64,
+    /// How points scroll to the left when old ones are dropped.
+    pub scroll: AnimationConfig,
+}
+
+impl Default for StreamOptions {
+    fn default() -> Self {
+        Self {
+            max_points: None,
+            max_age: None,
+            hysteresis: 0.25,
+            scroll: AnimationConfig::new(300, Easing::Linear),
+        }
+    }
+}
+
+struct StreamPoint {
+    /// Milliseconds since the Unix epoch.
+    time: f64,
+    label: String,
+    values: Vec<i32>,
+}
+
+struct StreamState {
+    options: StreamOptions,
+    points: VecDeque<StreamPoint>,
+    /// Points at the front of `points` that were dropped but are still scrolling out of view.
+    leaving: usize,
+    /// How many positions the lines still have to scroll, at the start of `scroll`.
+    scroll_from: f64,
+    scroll: Rc<RefCell<AnimationFrame>>,
+    y_max: f64,
+    /// Counts the changes to `points`, so that what is worked out from them can be kept
+    /// until they change again.
+    revision: u64,
+    redraw: Option<Rc<dyn Fn()>>,
+    animation: Option<Animation>,
+}
+
+impl StreamState {
+    fn live(&self) -> impl Iterator<Item = &StreamPoint> {
+        self.points.iter().skip(self.leaving)
+    }
+
+    /// How many positions the lines are still shifted to the right.
+    fn remaining_scroll(&self) -> f64 {
+        self.scroll_from * (1.0 - self.scroll.borrow().progress())
+    }
+
+    /// Drops points that have scrolled out of view completely.
+    fn settle(&mut self) {
+        let visible = (self.remaining_scroll().ceil() as usize).min(self.leaving);
+        if visible < self.leaving {
+            self.points.drain(..self.leaving - visible);
+            self.leaving = visible;
+            self.revision += 1;
+        }
+    }
+
+    /// Marks live points outside the window as leaving, and returns how many there were.
+    fn apply_window(&mut self) -> usize {
+        let newest = self.points.back().map_or(0.0, |point| point.time);
+        let max_points = self.options.max_points.unwrap_or(usize::MAX);
+        let max_age = self
+            .options
+            .max_age
+            .map_or(f64::INFINITY, |age| age.as_secs_f64() * 1000.0);
+
+        let mut dropped = 0;
+        while let Some(point) = self.points.get(self.leaving) {
+            let live = self.points.len() - self.leaving;
+            if live <= max_points && newest - point.time <= max_age {
+                break;
+            }
+            self.leaving += 1;
+            dropped += 1;
+        }
+        dropped
+    }
+
+    /// Grows the y-axis to fit the data right away, and shrinks it only once the data has
+    /// dropped below it by more than the hysteresis.
+    fn update_y_max(&mut self) {
+        let max = self
+            .live()
+            .flat_map(|point| point.values.iter())
+            .max()
+            .map_or(0.0, |&max| max as f64);
+
+        if max > self.y_max || max < self.y_max * (1.0 - self.options.hysteresis) {
+            self.y_max = max;
+        }
+    }
+
+    /// Where the first point is drawn, in positions along the x-axis. Negative while dropped
+    /// points scroll out on the left.
+    fn x_offset(&self) -> f64 {
+        self.remaining_scroll() - self.leaving as f64
+    }
+
+    /// How many points fit across the x-axis.
+    fn slots(&self) -> usize {
+        self.options
+            .max_points
+            .unwrap_or(self.points.len() - self.leaving)
+            .max(2)
+    }
+}
+
+/// The points of a [`LineStream`] on screen, including those still scrolling out, lent to a
+/// chart while it lays out a frame.
+#[derive(Clone, Copy)]
+pub(crate) struct StreamView<'a> {
+    state: &'a StreamState,
+}
+
+impl<'a> StreamView<'a> {
+    pub(crate) fn len(&self) -> usize {
+        self.state.points.len()
+    }
+
+    /// The x-axis label of the `index`-th point.
+    pub(crate) fn label(&self, index: usize) -> Option<&'a str> {
+        self.state
+            .points
+            .get(index)
+            .map(|point| point.label.as_str())
+    }
+
+    /// The value of the `series`-th series at the `index`-th point. Missing values are `0`.
+    pub(crate) fn value(&self, series: usize, index: usize) -> Option<i32> {
+        self.state
+            .points
+            .get(index)
+            .map(|point| point.values.get(series).copied().unwrap_or(0))
+    }
+
+    pub(crate) fn x_offset(&self) -> f64 {
+        self.state.x_offset()
+    }
+
+    pub(crate) fn slots(&self) -> usize {
+        self.state.slots()
+    }
+
+    pub(crate) fn y_max(&self) -> f64 {
+        self.state.y_max
+    }
+
+    /// Changes whenever the points do.
+    pub(crate) fn revision(&self) -> u64 {
+        self.state.revision
+    }
+}
+
+/// A handle for pushing live data into a
+/// [`LineCurveChart`](crate::charts::line_chart::line_chart::LineCurveChart) as it arrives.
+///
+/// Create one with [`use_line_stream`] and pass it to the chart's `stream` prop. The chart then
+/// takes its points and x-axis labels from the stream and only uses `data` for the series.
+/// Pushes redraw the canvas in the next animation frame, once for however many points arrived
+/// since the last one, without re-rendering the component or cloning the data that is already
+/// there, and points dropped from the window scroll out to the left.
+///
+/// The chart's ARIA label and hidden data table, which only change when it re-renders, catch
+/// up with the stream at most once a second.
+///
+///```ignore
+/// let stream = use_line_stream(StreamOptions {
+///     max_points: Some(60),
+///     ..Default::default()
+/// });
+///
+/// // e.g. from a WebSocket message or an interval
+/// stream.push(time_label, &[cpu, memory]);
+///
+/// html! {
+///     <LineCurveChart
+///         data={vec![(Series::new("CPU", ""), vec![]), (Series::new("Memory", ""), vec![])]}
+///         x={Vec::<String>::new()}
+///         stream={stream}
+///     />
+/// }
+/// ```
+#[derive(Clone)]
+pub struct LineStream {
+    state: Rc<RefCell<StreamState>>,
+}
+
+impl PartialEq for LineStream {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.state, &other.state)
+    }
+}
+
+impl fmt::Debug for LineStream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.state.borrow();
+        f.debug_struct("LineStream")
+            .field("options", &state.options)
+            .field("len", &(state.points.len() - state.leaving))
+            .finish()
+    }
+}
+
+impl Default for LineStream {
+    fn default() -> Self {
+        Self::new(StreamOptions::default())
+    }
+}
+
+impl LineStream {
+    pub fn new(options: StreamOptions) -> Self {
+        Self {
+            state: Rc::new(RefCell::new(StreamState {
+                options,
+                points: VecDeque::new(),
+                leaving: 0,
+                scroll_from: 0.0,
+                scroll: Rc::new(RefCell::new(AnimationFrame::complete())),
+                y_max: 0.0,
+                revision: 0,
+                redraw: None,
+                animation: None,
+            })),
+        }
+    }
+
+    /// Appends a point to every series, timestamped now. `values` holds one value per series,
+    /// in the order of the chart's `data`, and `label` is shown on the x-axis.
+    pub fn push(&self, label: impl Into<String>, values: &[i32]) {
+        self.push_at(Date::now(), label, values);
+    }
+
+    /// Appends a point to every series at `time`, in milliseconds since the Unix epoch. Points
+    /// are expected in time order.
+    pub fn push_at(&self, time: f64, label: impl Into<String>, values: &[i32]) {
+        let mut state = self.state.borrow_mut();
+        state.points.push_back(StreamPoint {
+            time,
+            label: label.into(),
+            values: values.to_vec(),
+        });
+        state.revision += 1;
+
+        let remaining = state.remaining_scroll();
+        let dropped = state.apply_window();
+        state.update_y_max();
+
+        let Some(redraw) = state.redraw.clone() else {
+            // Nothing is on screen to scroll
+            state.scroll_from = 0.0;
+            state.settle();
+            return;
+        };
+
+        if dropped == 0 {
+            drop(state);
+            redraw();
+            return;
+        }
+
+        // Scroll on from wherever a running scroll has got to
+        let scroll = Rc::new(RefCell::new(AnimationFrame::start(None)));
+        state.scroll_from = remaining + dropped as f64;
+        state.scroll = scroll.clone();
+        state.settle();
+        let options = state.options.scroll;
+        drop(state);
+
+        let animation = animate(&options, scroll, redraw);
+        self.state.borrow_mut().animation = Some(animation);
+    }
+
+    /// Keeps only the last `count` points.
+    pub fn trim_to_points(&self, count: usize) {
+        self.trim(|state| {
+            let live = state.points.len() - state.leaving;
+            state
+                .points
+                .drain(..state.leaving + live.saturating_sub(count));
+        });
+    }
+
+    /// Keeps only the points at most `age` older than the newest one.
+    pub fn trim_to_age(&self, age: Duration) {
+        self.trim(|state| {
+            let oldest =
+                state.points.back().map_or(0.0, |point| point.time) - age.as_secs_f64() * 1000.0;
+            let stale = state
+                .points
+                .iter()
+                .position(|point| point.time >= oldest)
+                .unwrap_or(state.points.len());
+            state.points.drain(..stale.max(state.leaving));
+        });
+    }
+
+    /// Removes every point.
+    pub fn clear(&self) {
+        self.trim(|state| state.points.clear());
+    }
+
+    /// Drops points right away, without scrolling, and redraws the chart.
+    fn trim(&self, drop_points: impl FnOnce(&mut StreamState)) {
+        let mut state = self.state.borrow_mut();
+        drop_points(&mut state);
+        state.leaving = 0;
+        state.revision += 1;
+        state.scroll_from = 0.0;
+        state.animation.take();
+        state.update_y_max();
+
+        let redraw = state.redraw.clone();
+        drop(state);
+        if let Some(redraw) = redraw {
+            redraw();
+        }
+    }
+
+    /// The number of points in each series.
+    pub fn len(&self) -> usize {
+        let state = self.state.borrow();
+        state.points.len() - state.leaving
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The largest value the y-axis is currently scaled for. It follows the data with the
+    /// configured hysteresis, so it can be larger than the largest value in the window.
+    pub fn y_max(&self) -> f64 {
+        self.state.borrow().y_max
+    }
+
+    /// Drops points that have scrolled out of view and lends the rest to `f`, without copying
+    /// them.
+    pub(crate) fn view<R>(&self, f: impl FnOnce(StreamView<'_>) -> R) -> R {
+        self.state.borrow_mut().settle();
+        f(StreamView {
+            state: &self.state.borrow(),
+        })
+    }
+
+    /// Registers the function that redraws the chart showing this stream. It is called on
+    /// every push, so it should only schedule the redraw.
+    pub(crate) fn attach(&self, redraw: Rc<dyn Fn()>) {
+        self.state.borrow_mut().redraw = Some(redraw);
+    }
+
+    pub(crate) fn detach(&self) {
+        let mut state = self.state.borrow_mut();
+        state.redraw = None;
+        state.animation = None;
+    }
+}
+
+/// How often a chart showing a stream re-renders, at most, to catch up with what was pushed.
+const RENDER_INTERVAL_MS: u32 = 1000;
+
+/// The revision of the points of `stream`, checked at most once a second, so that a chart
+/// re-renders what isn't redrawn on every push, such as its description for screen readers.
+#[hook]
+pub(crate) fn use_stream_revision(stream: Option<LineStream>) -> u64 {
+    let revision = use_state_eq(|| 0);
+    {
+        let revision = revision.clone();
+        use_effect_with(stream, move |stream| {
+            let interval = stream.clone().map(|stream| {
+                Interval::new(RENDER_INTERVAL_MS, move || {
+                    revision.set(stream.state.borrow().revision)
+                })
+            });
+            move || drop(interval)
+        });
+    }
+
+    *revision
+}
+
+/// Keeps the same [`LineStream`] across renders of a component.
+#[hook]
+pub fn use_line_stream(options: StreamOptions) -> LineStream {
+    (*use_state(|| LineStream::new(options))).clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(stream: &LineStream) -> Vec<i32> {
+        stream.view(|view| (0..view.len()).filter_map(|i| view.value(0, i)).collect())
+    }
+
+    fn labels(stream: &LineStream) -> Vec<String> {
+        stream.view(|view| {
+            (0..view.len())
+                .filter_map(|i| view.label(i).map(str::to_string))
+                .collect()
+        })
+    }
+
+    fn x_offset(stream: &LineStream) -> f64 {
+        stream.view(|view| view.x_offset())
+    }
+
+    #[test]
+    fn keeps_the_last_points_and_seconds() {
+        let stream = LineStream::new(StreamOptions {
+            max_points: Some(3),
+            max_age: Some(Duration::from_secs(10)),
+            ..Default::default()
+        });
+        for (i, time) in [0.0, 1000.0, 2000.0, 3000.0].into_iter().enumerate() {
+            stream.push_at(time, i.to_string(), &[i as i32]);
+        }
+        assert_eq!(values(&stream), vec![1, 2, 3]);
+        assert_eq!(labels(&stream), vec!["1", "2", "3"]);
+
+        // Only the newest point is within ten seconds of itself
+        stream.push_at(14_000.0, "4", &[4]);
+        assert_eq!(values(&stream), vec![4]);
+        assert_eq!(stream.view(|view| view.slots()), 3);
+
+        stream.push_at(15_000.0, "5", &[5]);
+        stream.trim_to_points(1);
+        assert_eq!(values(&stream), vec![5]);
+        stream.push_at(20_000.0, "6", &[6]);
+        stream.trim_to_age(Duration::from_secs(2));
+        assert_eq!(values(&stream), vec![6]);
+        assert_eq!(x_offset(&stream), 0.0);
+    }
+
+    #[test]
+    fn y_axis_shrinks_with_hysteresis() {
+        let stream = LineStream::new(StreamOptions {
+            max_points: Some(2),
+            hysteresis: 0.5,
+            ..Default::default()
+        });
+        stream.push_at(0.0, "", &[100]);
+        assert_eq!(stream.y_max(), 100.0);
+
+        // Less than half of the axis is too little to rescale, but a new maximum grows it
+        stream.push_at(1.0, "", &[60]);
+        stream.push_at(2.0, "", &[60]);
+        assert_eq!(stream.y_max(), 100.0);
+        stream.push_at(3.0, "", &[120]);
+        assert_eq!(stream.y_max(), 120.0);
+
+        stream.push_at(4.0, "", &[50]);
+        stream.push_at(5.0, "", &[50]);
+        assert_eq!(stream.y_max(), 50.0);
+    }
+
+    #[test]
+    fn dropped_points_scroll_out_before_they_are_removed() {
+        let stream = LineStream::new(StreamOptions {
+            max_points: Some(2),
+            ..Default::default()
+        });
+        stream.push_at(0.0, "a", &[1]);
+        stream.push_at(1.0, "b", &[2]);
+
+        // Halfway through scrolling out two dropped points
+        {
+            let mut state = stream.state.borrow_mut();
+            state.points.push_back(StreamPoint {
+                time: 2.0,
+                label: "c".into(),
+                values: vec![3],
+            });
+            state.points.push_back(StreamPoint {
+                time: 3.0,
+                label: "d".into(),
+                values: vec![4],
+            });
+            assert_eq!(state.apply_window(), 2);
+            state.scroll_from = 2.0;
+            state.scroll.borrow_mut().set_progress(0.5);
+        }
+        assert_eq!(stream.len(), 2);
+        assert_eq!(values(&stream), vec![2, 3, 4]);
+        assert_eq!(x_offset(&stream), 0.0);
+
+        stream.state.borrow().scroll.borrow_mut().set_progress(0.75);
+        assert_eq!(x_offset(&stream), -0.5);
+        stream.state.borrow().scroll.borrow_mut().set_progress(1.0);
+        assert_eq!(values(&stream), vec![3, 4]);
+        assert_eq!(x_offset(&stream), 0.0);
+    }
+}
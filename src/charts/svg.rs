@@ -0,0 +1,706 @@
+use std::cell::RefCell;
+use std::fmt::Write;
+
+use crate::charts::canvas::{arc_curves, arc_sweep, Canvas, Chart, Transform};
+use crate::charts::legend::LegendLayout;
+use crate::charts::pattern::{Pattern, TILE_SIZE};
+use crate::charts::text::{estimated_text_width, is_font_size, number};
+
+/// Renders `chart` as a standalone SVG document, with its legend placed as the chart's
+/// legend config says.
+///
+/// The chart area is `width` by `height` pixels and the document grows to fit a legend above,
+/// below or beside the chart. This doesn't need a browser, so it also works in native code such as a backend job
+/// generating report images. The chart is drawn with its `theme` prop, or [`Theme::light`]
+/// when it doesn't set one.
+///
+///```
+/// # use visualize_yew::charts::pie_chart::pie_chart::{DataPoint, PieChartProps};
+/// # use visualize_yew::svg::to_svg;
+/// let props = PieChartProps {
+///     data: vec![DataPoint::new("A", 10, ""), DataPoint::new("B", 20, "")],
+///     config: Default::default(),
+///     ..Default::default()
+/// };
+///
+/// let svg = to_svg(&props, 400.0, 300.0);
+/// assert!(svg.starts_with("<svg"));
+/// ```
+///
+/// [`Theme::light`]: crate::theme::Theme::light
+pub fn to_svg(chart: &dyn Chart, width: f64, height: f64) -> String {
+    let theme = chart.theme().cloned().unwrap_or_default();
+    let canvas = SvgCanvas::new();
+
+    let legend = chart.legend();
+    let legend_layout = LegendLayout::measure(
+        &canvas,
+        &legend,
+        &chart.legend_config(),
+        &theme,
+        (width, height),
+        chart.plot_inset(&theme),
+    );
+
+    canvas.save();
+    let (x, y) = legend_layout.chart;
+    canvas.translate(x, y);
+    chart.draw(&canvas, width, height, &theme);
+    canvas.restore();
+
+    legend_layout.draw(&canvas, &legend, &theme);
+
+    let (width, height) = legend_layout.size;
+    canvas.to_document(width, height)
+}
+
+/// A [`Canvas`] that records what is drawn as SVG elements.
+///
+/// Transforms are applied to the coordinates as they are drawn, so the resulting document is
+/// made of plain paths and text that vector editors can work with directly. `clear_rect` has
+/// no effect, since there is nothing to clear in a document that starts out empty.
+#[derive(Debug, Default)]
+pub struct SvgCanvas {
+    inner: RefCell<Inner>,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    state: State,
+    saved: Vec<State>,
+    /// Path data for the current path, in document coordinates.
+    path: String,
+    has_current_point: bool,
+    defs: Vec<String>,
+    elements: Vec<String>,
+    patterns: Vec<(Pattern, String)>,
+    clip_count: usize,
+}
+
+#[derive(Clone, Debug)]
+struct State {
+    fill: String,
+    stroke: String,
+    line_width: f64,
+    alpha: f64,
+    line_dash: Vec<f64>,
+    font: String,
+    text_align: String,
+    text_baseline: String,
+    transform: Transform,
+    clip: Option<String>,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        // The initial values of a canvas context
+        Self {
+            fill: "#000000".into(),
+            stroke: "#000000".into(),
+            line_width: 1.0,
+            alpha: 1.0,
+            line_dash: Vec::new(),
+            font: "10px sans-serif".into(),
+            text_align: "start".into(),
+            text_baseline: "alphabetic".into(),
+            transform: Transform::default(),
+            clip: None,
+        }
+    }
+}
+
+impl SvgCanvas {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Wraps everything drawn so far in an SVG document of the given size.
+    pub fn to_document(&self, width: f64, height: f64) -> String {
+        let inner = self.inner.borrow();
+        let mut document = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">\n",
+            w = number(width),
+            h = number(height)
+        );
+
+        if !inner.defs.is_empty() {
+            document.push_str("<defs>\n");
+            for def in &inner.defs {
+                document.push_str(def);
+                document.push('\n');
+            }
+            document.push_str("</defs>\n");
+        }
+        for element in &inner.elements {
+            document.push_str(element);
+            document.push('\n');
+        }
+        document.push_str("</svg>\n");
+
+        document
+    }
+}
+
+impl Inner {
+    fn point(&self, x: f64, y: f64) -> String {
+        let (x, y) = self.state.transform.apply(x, y);
+        format!("{} {}", number(x), number(y))
+    }
+
+    fn path_to(&mut self, x: f64, y: f64) {
+        let command = if self.has_current_point { "L" } else { "M" };
+        let point = self.point(x, y);
+        let _ignored_result = write!(self.path, "{}{}", command, point);
+        self.has_current_point = true;
+    }
+
+    fn arc(&mut self, x: f64, y: f64, radius: f64, start_angle: f64, sweep: f64) {
+        let ((start_x, start_y), curves) = arc_curves(x, y, radius, start_angle, sweep);
+        self.path_to(start_x, start_y);
+
+        for [(cp1x, cp1y), (cp2x, cp2y), (end_x, end_y)] in curves {
+            let (control_1, control_2, end) = (
+                self.point(cp1x, cp1y),
+                self.point(cp2x, cp2y),
+                self.point(end_x, end_y),
+            );
+            let _ignored_result = write!(self.path, "C{} {} {}", control_1, control_2, end);
+        }
+    }
+
+    /// The path for a rectangle, without touching the current path.
+    fn rect_path(&self, x: f64, y: f64, width: f64, height: f64) -> String {
+        format!(
+            "M{}L{}L{}L{}Z",
+            self.point(x, y),
+            self.point(x + width, y),
+            self.point(x + width, y + height),
+            self.point(x, y + height)
+        )
+    }
+
+    fn clip_attribute(&self) -> String {
+        match &self.state.clip {
+            Some(id) => format!(" clip-path=\"url(#{})\"", id),
+            None => String::new(),
+        }
+    }
+
+    fn opacity_attribute(&self) -> String {
+        match self.state.alpha < 1.0 {
+            true => format!(" opacity=\"{}\"", number(self.state.alpha)),
+            false => String::new(),
+        }
+    }
+
+    fn fill_path(&mut self, path: String, fill: &str) {
+        if path.is_empty() {
+            return;
+        }
+
+        let element = format!(
+            "<path d=\"{}\" fill=\"{}\"{}{}/>",
+            path,
+            escape(fill),
+            self.opacity_attribute(),
+            self.clip_attribute()
+        );
+        self.elements.push(element);
+    }
+
+    fn stroke_path(&mut self, path: String) {
+        if path.is_empty() {
+            return;
+        }
+
+        let dash = match self.state.line_dash.is_empty() {
+            true => String::new(),
+            false => format!(
+                " stroke-dasharray=\"{}\"",
+                self.state
+                    .line_dash
+                    .iter()
+                    .map(|&segment| number(segment))
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            ),
+        };
+        let element = format!(
+            "<path d=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"{}\"{}{}{}/>",
+            path,
+            escape(&self.state.stroke),
+            number(self.state.line_width),
+            dash,
+            self.opacity_attribute(),
+            self.clip_attribute()
+        );
+        self.elements.push(element);
+    }
+
+    /// The id of the `<pattern>` for `pattern` drawn in `ink`, defining it on first use.
+    fn pattern_id(&mut self, pattern: Pattern, ink: &str) -> String {
+        let index = match self
+            .patterns
+            .iter()
+            .position(|(known, known_ink)| *known == pattern && known_ink == ink)
+        {
+            Some(index) => index,
+            None => {
+                self.patterns.push((pattern, ink.to_string()));
+                let index = self.patterns.len() - 1;
+                self.defs.push(format!(
+                    "<pattern id=\"pattern{}\" width=\"{size}\" height=\"{size}\" patternUnits=\"userSpaceOnUse\">{}</pattern>",
+                    index,
+                    pattern.svg_marks(&escape(ink)),
+                    size = TILE_SIZE
+                ));
+                index
+            }
+        };
+
+        format!("pattern{}", index)
+    }
+}
+
+impl Canvas for SvgCanvas {
+    fn save(&self) {
+        let mut inner = self.inner.borrow_mut();
+        let state = inner.state.clone();
+        inner.saved.push(state);
+    }
+
+    fn restore(&self) {
+        let mut inner = self.inner.borrow_mut();
+        if let Some(state) = inner.saved.pop() {
+            inner.state = state;
+        }
+    }
+
+    fn translate(&self, x: f64, y: f64) {
+        self.inner.borrow_mut().state.transform.translate(x, y);
+    }
+
+    fn rotate(&self, angle: f64) {
+        self.inner.borrow_mut().state.transform.rotate(angle);
+    }
+
+    fn set_fill_style(&self, color: &str) {
+        self.inner.borrow_mut().state.fill = color.to_string();
+    }
+
+    fn set_stroke_style(&self, color: &str) {
+        self.inner.borrow_mut().state.stroke = color.to_string();
+    }
+
+    fn set_line_width(&self, width: f64) {
+        self.inner.borrow_mut().state.line_width = width;
+    }
+
+    fn set_global_alpha(&self, alpha: f64) {
+        self.inner.borrow_mut().state.alpha = alpha;
+    }
+
+    fn set_line_dash(&self, segments: &[f64]) {
+        self.inner.borrow_mut().state.line_dash = segments.to_vec();
+    }
+
+    fn set_font(&self, font: &str) {
+        self.inner.borrow_mut().state.font = font.to_string();
+    }
+
+    fn set_text_align(&self, align: &str) {
+        self.inner.borrow_mut().state.text_align = align.to_string();
+    }
+
+    fn set_text_baseline(&self, baseline: &str) {
+        self.inner.borrow_mut().state.text_baseline = baseline.to_string();
+    }
+
+    fn clear_rect(&self, _x: f64, _y: f64, _width: f64, _height: f64) {}
+
+    fn fill_rect(&self, x: f64, y: f64, width: f64, height: f64) {
+        let mut inner = self.inner.borrow_mut();
+        let path = inner.rect_path(x, y, width, height);
+        let fill = inner.state.fill.clone();
+        inner.fill_path(path, &fill);
+    }
+
+    fn stroke_rect(&self, x: f64, y: f64, width: f64, height: f64) {
+        let mut inner = self.inner.borrow_mut();
+        let path = inner.rect_path(x, y, width, height);
+        inner.stroke_path(path);
+    }
+
+    fn fill_text(&self, text: &str, x: f64, y: f64) {
+        let mut inner = self.inner.borrow_mut();
+        let state = &inner.state;
+
+        let (x, y) = state.transform.apply(x, y);
+        let rotation = state.transform.rotation_degrees();
+        let position = if rotation.abs() < 1e-9 {
+            format!("x=\"{}\" y=\"{}\"", number(x), number(y))
+        } else {
+            format!(
+                "transform=\"translate({} {}) rotate({})\"",
+                number(x),
+                number(y),
+                number(rotation)
+            )
+        };
+
+        let anchor = match state.text_align.as_str() {
+            "center" => " text-anchor=\"middle\"",
+            "right" | "end" => " text-anchor=\"end\"",
+            _ => "",
+        };
+        let baseline = match state.text_baseline.as_str() {
+            "middle" => " dominant-baseline=\"middle\"",
+            "top" | "hanging" => " dominant-baseline=\"hanging\"",
+            "bottom" | "ideographic" => " dominant-baseline=\"text-after-edge\"",
+            _ => "",
+        };
+
+        let element = format!(
+            "<text {}{} fill=\"{}\"{}{}{}{}>{}</text>",
+            position,
+            font_attributes(&state.font),
+            escape(&state.fill),
+            anchor,
+            baseline,
+            inner.opacity_attribute(),
+            inner.clip_attribute(),
+            escape(text)
+        );
+        inner.elements.push(element);
+    }
+
+    /// Estimates the width from the font size, since there are no font metrics to go by.
+    fn measure_text(&self, text: &str) -> f64 {
+        estimated_text_width(&self.inner.borrow().state.font, text)
+    }
+
+    fn begin_path(&self) {
+        let mut inner = self.inner.borrow_mut();
+        inner.path.clear();
+        inner.has_current_point = false;
+    }
+
+    fn close_path(&self) {
+        let mut inner = self.inner.borrow_mut();
+        if inner.has_current_point {
+            inner.path.push('Z');
+        }
+    }
+
+    fn move_to(&self, x: f64, y: f64) {
+        let mut inner = self.inner.borrow_mut();
+        let point = inner.point(x, y);
+        let _ignored_result = write!(inner.path, "M{}", point);
+        inner.has_current_point = true;
+    }
+
+    fn line_to(&self, x: f64, y: f64) {
+        self.inner.borrow_mut().path_to(x, y);
+    }
+
+    fn bezier_curve_to(&self, cp1x: f64, cp1y: f64, cp2x: f64, cp2y: f64, x: f64, y: f64) {
+        let mut inner = self.inner.borrow_mut();
+        if !inner.has_current_point {
+            inner.path_to(cp1x, cp1y);
+        }
+        let (control_1, control_2, end) = (
+            inner.point(cp1x, cp1y),
+            inner.point(cp2x, cp2y),
+            inner.point(x, y),
+        );
+        let _ignored_result = write!(inner.path, "C{} {} {}", control_1, control_2, end);
+    }
+
+    fn arc(&self, x: f64, y: f64, radius: f64, start_angle: f64, end_angle: f64) {
+        let sweep = arc_sweep(start_angle, end_angle, false);
+        self.inner
+            .borrow_mut()
+            .arc(x, y, radius, start_angle, sweep);
+    }
+
+    fn arc_anticlockwise(&self, x: f64, y: f64, radius: f64, start_angle: f64, end_angle: f64) {
+        let sweep = arc_sweep(start_angle, end_angle, true);
+        self.inner
+            .borrow_mut()
+            .arc(x, y, radius, start_angle, sweep);
+    }
+
+    fn rect(&self, x: f64, y: f64, width: f64, height: f64) {
+        let mut inner = self.inner.borrow_mut();
+        let path = inner.rect_path(x, y, width, height);
+        inner.path.push_str(&path);
+        inner.has_current_point = true;
+    }
+
+    fn fill(&self) {
+        let mut inner = self.inner.borrow_mut();
+        let (path, fill) = (inner.path.clone(), inner.state.fill.clone());
+        inner.fill_path(path, &fill);
+    }
+
+    fn stroke(&self) {
+        let mut inner = self.inner.borrow_mut();
+        let path = inner.path.clone();
+        inner.stroke_path(path);
+    }
+
+    fn clip(&self) {
+        let mut inner = self.inner.borrow_mut();
+        let id = format!("clip{}", inner.clip_count);
+        inner.clip_count += 1;
+
+        // Clipping inside a clip intersects the two, like on a canvas
+        let clip_path = format!(
+            "<clipPath id=\"{}\"{}><path d=\"{}\"/></clipPath>",
+            id,
+            inner.clip_attribute(),
+            inner.path
+        );
+        inner.defs.push(clip_path);
+        inner.state.clip = Some(id);
+    }
+
+    fn fill_pattern(&self, pattern: Pattern, ink: &str) {
+        let mut inner = self.inner.borrow_mut();
+        let fill = format!("url(#{})", inner.pattern_id(pattern, ink));
+        let path = inner.path.clone();
+        inner.fill_path(path, &fill);
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Splits a CSS font shorthand such as `"bold 12px Arial"` into SVG font attributes.
+fn font_attributes(font: &str) -> String {
+    let tokens = font.split_whitespace().collect::<Vec<&str>>();
+    let Some(size_index) = tokens.iter().position(|token| is_font_size(token)) else {
+        return format!(" style=\"font: {}\"", escape(font));
+    };
+
+    let mut attributes = String::new();
+    for token in &tokens[..size_index] {
+        match *token {
+            "italic" | "oblique" => {
+                let _ignored_result = write!(attributes, " font-style=\"{}\"", token);
+            }
+            "bold" | "bolder" | "lighter" => {
+                let _ignored_result = write!(attributes, " font-weight=\"{}\"", token);
+            }
+            weight if weight.parse::<u32>().is_ok() => {
+                let _ignored_result = write!(attributes, " font-weight=\"{}\"", weight);
+            }
+            _ => {}
+        }
+    }
+
+    // Drop the line height, e.g. "12px/1.5"
+    let size = tokens[size_index].split('/').next().unwrap_or_default();
+    let family = tokens[size_index + 1..].join(" ");
+    let _ignored_result = write!(attributes, " font-size=\"{}\"", escape(size));
+    if !family.is_empty() {
+        let _ignored_result = write!(attributes, " font-family=\"{}\"", escape(&family));
+    }
+
+    attributes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::charts::legend::{LegendConfig, LegendPosition};
+    use crate::charts::line_chart::line_chart::{
+        DataPoint, LineCurveChartConfig, LineCurveChartProps, Series,
+    };
+    use crate::charts::pie_chart::pie_chart::{
+        DataPoint as PieDataPoint, PieChartConfig, PieChartProps,
+    };
+    use crate::charts::scene::SceneItem;
+    use crate::charts::theme::Theme;
+    use crate::charts::title::ChartTitles;
+    use std::f64::consts::PI;
+
+    #[test]
+    fn draws_rects_and_text_through_transforms() {
+        let canvas = SvgCanvas::new();
+        canvas.set_fill_style("red");
+        canvas.translate(10.0, 20.0);
+        canvas.fill_rect(0.0, 0.0, 5.0, 5.0);
+        canvas.rotate(-PI / 2.0);
+        canvas.set_font("bold 12px Arial");
+        canvas.set_text_align("center");
+        canvas.fill_text("a < b", 0.0, 0.0);
+
+        assert_eq!(
+            canvas.to_document(100.0, 50.0),
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"100\" height=\"50\" viewBox=\"0 0 100 50\">\n\
+             <path d=\"M10 20L15 20L15 25L10 25Z\" fill=\"red\"/>\n\
+             <text transform=\"translate(10 20) rotate(-90)\" font-weight=\"bold\" font-size=\"12px\" font-family=\"Arial\" fill=\"red\" text-anchor=\"middle\">a &lt; b</text>\n\
+             </svg>\n"
+        );
+    }
+
+    #[test]
+    fn arcs_become_bezier_curves() {
+        let canvas = SvgCanvas::new();
+        canvas.begin_path();
+        canvas.arc(0.0, 0.0, 10.0, 0.0, PI / 2.0);
+        canvas.stroke();
+
+        assert!(canvas
+            .to_document(10.0, 10.0)
+            .contains("<path d=\"M10 0C10 5.52 5.52 10 0 10\" fill=\"none\" stroke=\"#000000\" stroke-width=\"1\"/>"));
+    }
+
+    #[test]
+    fn full_circles_use_four_curves() {
+        let canvas = SvgCanvas::new();
+        canvas.begin_path();
+        canvas.arc(0.0, 0.0, 10.0, 0.0, 2.0 * PI);
+        canvas.fill();
+
+        assert_eq!(canvas.to_document(10.0, 10.0).matches('C').count(), 4);
+    }
+
+    #[test]
+    fn clips_and_patterns_are_defined_once() {
+        let canvas = SvgCanvas::new();
+        canvas.begin_path();
+        canvas.rect(0.0, 0.0, 10.0, 10.0);
+        canvas.clip();
+        canvas.fill_pattern(Pattern::Dots, "black");
+        canvas.fill_pattern(Pattern::Dots, "black");
+
+        let document = canvas.to_document(10.0, 10.0);
+        assert_eq!(document.matches("<pattern ").count(), 1);
+        assert_eq!(document.matches("<clipPath ").count(), 1);
+        assert_eq!(
+            document
+                .matches("fill=\"url(#pattern0)\" clip-path=\"url(#clip0)\"")
+                .count(),
+            2
+        );
+    }
+
+    #[test]
+    fn parses_canvas_fonts() {
+        assert_eq!(
+            font_attributes("italic 600 14px/1.5 \"Helvetica Neue\", sans-serif"),
+            " font-style=\"italic\" font-weight=\"600\" font-size=\"14px\" \
+             font-family=\"&quot;Helvetica Neue&quot;, sans-serif\""
+        );
+    }
+
+    #[test]
+    fn global_alpha_becomes_opacity() {
+        let canvas = SvgCanvas::new();
+        canvas.set_fill_style("rgb(54 162 235)");
+        canvas.set_global_alpha(0.25);
+        canvas.fill_rect(0.0, 0.0, 5.0, 5.0);
+        canvas.set_global_alpha(1.0);
+        canvas.fill_rect(5.0, 0.0, 5.0, 5.0);
+
+        let document = canvas.to_document(10.0, 5.0);
+        assert!(document.contains("fill=\"rgb(54 162 235)\" opacity=\"0.25\"/>"));
+        assert_eq!(document.matches("opacity").count(), 1);
+    }
+
+    #[test]
+    fn renders_charts_with_legend() {
+        let props = PieChartProps {
+            data: vec![
+                PieDataPoint::new("Apples", 10, ""),
+                PieDataPoint::new("Pears", 20, "").with_pattern(Pattern::CrossHatch),
+            ],
+            config: PieChartConfig {
+                show_legend: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let svg = to_svg(&props, 200.0, 100.0);
+        assert!(svg
+            .starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"200\" height=\"114\""));
+        assert!(svg.contains(">Apples</text>"));
+        assert!(svg.contains(">Pears</text>"));
+        assert!(svg.contains("<pattern id=\"pattern0\""));
+    }
+
+    #[test]
+    fn places_the_legend_as_configured() {
+        let props = PieChartProps {
+            data: vec![PieDataPoint::new("Apples", 10, "")],
+            config: PieChartConfig {
+                show_legend: true,
+                legend: LegendConfig::default().with_position(LegendPosition::Right),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // The document grows wider for the legend rather than taller
+        let svg = to_svg(&props, 200.0, 100.0);
+        let size = svg.split('>').next().unwrap();
+        assert!(size.contains("height=\"100\""));
+        assert!(!size.contains("width=\"200\""));
+        assert!(svg.contains(">Apples</text>"));
+    }
+
+    #[test]
+    fn renders_axis_titles() {
+        let props = LineCurveChartProps {
+            data: vec![(
+                Series::new("Visits", ""),
+                vec![DataPoint::new(1), DataPoint::new(3)],
+            )],
+            x: vec!["Mon".to_string(), "Tue".to_string()],
+            config: LineCurveChartConfig {
+                show_x_axis_labels: true,
+                x_axis_title: "Day".to_string(),
+                y_axis_title: "Count".to_string(),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let svg = to_svg(&props, 300.0, 200.0);
+        assert!(svg.contains(">Day</text>"));
+        assert!(svg.contains("rotate(-90)\""));
+        assert!(svg.contains(">Count</text>"));
+        assert!(svg.contains(">Tue</text>"));
+    }
+
+    #[test]
+    fn renders_titles_around_the_chart() {
+        let props = PieChartProps {
+            data: vec![PieDataPoint::new("Apples", 10, "")],
+            config: PieChartConfig {
+                show_legend: false,
+                titles: ChartTitles::new("Fruit").with_caption("Source: market"),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let svg = to_svg(&props, 200.0, 200.0);
+        assert!(svg.contains(">Fruit</text>"));
+        assert!(svg.contains(">Source: market</text>"));
+        // The pie shrinks to fit between them rather than running into them
+        let pie = Chart::scene(&props, 200.0, 200.0, &Theme::light());
+        let SceneItem::Sector(sector) = &pie.items[0] else {
+            panic!("expected a slice");
+        };
+        assert!(sector.center.1 - sector.radius > 31.0);
+        assert!(sector.center.1 + sector.radius < 200.0 - 28.5);
+    }
+}
@@ -0,0 +1,55 @@
+/// Formats a coordinate with at most two decimals, which is plenty for screen and print.
+pub(crate) fn number(value: f64) -> String {
+    let rounded = (value * 100.0).round() / 100.0;
+    if rounded == 0.0 {
+        // Avoid "-0"
+        return "0".to_string();
+    }
+
+    format!("{}", rounded)
+}
+
+/// Whether a token of a CSS font shorthand is its size, e.g. `12px` or `1.2em/1.5`.
+pub(crate) fn is_font_size(token: &str) -> bool {
+    let size = token.split('/').next().unwrap_or_default();
+    size.starts_with(|c: char| c.is_ascii_digit() || c == '.')
+        && ["px", "pt", "em", "rem", "%"]
+            .iter()
+            .any(|unit| size.ends_with(unit))
+}
+
+/// The width of `text` in `font`, estimated from the font size for when there are no font
+/// metrics to go by.
+pub(crate) fn estimated_text_width(font: &str, text: &str) -> f64 {
+    font_size(font) * 0.6 * text.chars().count() as f64
+}
+
+/// The font size in pixels, falling back to the canvas default of 10px.
+pub(crate) fn font_size(font: &str) -> f64 {
+    font.split_whitespace()
+        .find(|token| is_font_size(token))
+        .and_then(|token| token.split('/').next())
+        .and_then(|size| size.strip_suffix("px"))
+        .and_then(|size| size.parse().ok())
+        .unwrap_or(10.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn measures_fonts() {
+        assert_eq!(font_size("bold 12px Arial"), 12.0);
+        assert_eq!(font_size("12px/1.5 Arial"), 12.0);
+        assert_eq!(font_size("caption"), 10.0);
+        assert_eq!(estimated_text_width("10px Arial", "abc"), 18.0);
+    }
+
+    #[test]
+    fn formats_numbers_with_two_decimals() {
+        assert_eq!(number(1.005), "1");
+        assert_eq!(number(2.5), "2.5");
+        assert_eq!(number(-0.001), "0");
+    }
+}
@@ -0,0 +1,381 @@
+use gloo::events::EventListener;
+use web_sys::{window, Element};
+use yew::prelude::*;
+
+/// Colors and fonts shared by every chart.
+///
+/// A theme is usually provided once near the root of the app through a [`ThemeProvider`]
+/// and picked up by every chart below it. Each chart also accepts a `theme` prop which,
+/// when set, takes precedence over the provided one.
+///
+/// Any value may reference CSS custom properties, e.g. `"var(--color-fg, black)"`. These are
+/// resolved against the computed style of the chart's container every time the chart is drawn.
+///
+///```
+/// # use visualize_yew::theme::Theme;
+/// let mut theme = Theme::dark();
+/// theme.text_color = "var(--color-fg)".to_string();
+/// theme.palette = vec!["#ff6384".to_string(), "var(--color-accent, #36a2eb)".to_string()];
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Theme {
+    /// Painted behind the chart before anything else is drawn.
+    pub background: String,
+    /// Used for axis labels, tick labels and titles.
+    pub text_color: String,
+    pub grid_color: String,
+    pub axis_color: String,
+    /// Outline drawn between pie and doughnut slices.
+    pub border_color: String,
+    pub tooltip_background: String,
+    pub tooltip_text_color: String,
+    /// Ink for [`crate::pattern::Pattern`] fills, drawn on top of the fill color.
+    pub pattern_color: String,
+    /// Fill of the translucent rectangle drawn over a brushed range.
+    pub selection_color: String,
+    /// Canvas font used for tick labels, e.g. `"10px sans-serif"`.
+    pub font: String,
+    /// Canvas font used for axis titles, e.g. `"bold 12px Arial"`.
+    pub title_font: String,
+    /// Colors picked in order for data points and series that don't set their own color.
+    pub palette: Vec<String>,
+}
+
+impl Theme {
+    /// The default theme, dark text on a transparent background.
+    pub fn light() -> Self {
+        Self {
+            background: "transparent".into(),
+            text_color: "black".into(),
+            grid_color: "#cccccc".into(),
+            axis_color: "#cccccc".into(),
+            border_color: "white".into(),
+            tooltip_background: "rgba(0, 0, 0, 0.8)".into(),
+            tooltip_text_color: "white".into(),
+            pattern_color: "rgba(0, 0, 0, 0.45)".into(),
+            selection_color: "rgba(54, 162, 235, 0.2)".into(),
+            font: "10px sans-serif".into(),
+            title_font: "bold 12px Arial".into(),
+            palette: default_palette(),
+        }
+    }
+
+    /// Light text and muted lines for charts placed on dark surfaces.
+    pub fn dark() -> Self {
+        Self {
+            background: "transparent".into(),
+            text_color: "#e5e7eb".into(),
+            grid_color: "#374151".into(),
+            axis_color: "#6b7280".into(),
+            border_color: "#111827".into(),
+            tooltip_background: "rgba(243, 244, 246, 0.95)".into(),
+            tooltip_text_color: "#111827".into(),
+            pattern_color: "rgba(255, 255, 255, 0.55)".into(),
+            selection_color: "rgba(147, 197, 253, 0.25)".into(),
+            font: "10px sans-serif".into(),
+            title_font: "bold 12px Arial".into(),
+            palette: default_palette(),
+        }
+    }
+
+    /// Returns the palette color for the given index, wrapping around when the palette is shorter
+    /// than the data.
+    pub fn palette_color(&self, index: usize) -> &str {
+        if self.palette.is_empty() {
+            return &self.text_color;
+        }
+
+        &self.palette[index % self.palette.len()]
+    }
+
+    /// Returns `color` unless it is empty, in which case the palette color for `index` is used.
+    pub fn color_or_palette<'a>(&'a self, color: &'a str, index: usize) -> &'a str {
+        if color.is_empty() {
+            self.palette_color(index)
+        } else {
+            color
+        }
+    }
+
+    /// Returns a copy of the theme with every `var(--name)` reference replaced by the value of
+    /// that custom property on `element`.
+    ///
+    /// Canvas drawing can't see CSS, so charts call this on their container before drawing.
+    /// References to undefined properties fall back to the `var()` fallback if one is given.
+    pub fn resolve_css_variables(&self, element: &Element) -> Theme {
+        if !self.uses_css_variables() {
+            return self.clone();
+        }
+
+        let style = window().and_then(|window| window.get_computed_style(element).ok().flatten());
+        let lookup = |name: &str| {
+            style
+                .as_ref()
+                .and_then(|style| style.get_property_value(name).ok())
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+        };
+
+        self.map_values(|value| resolve_var(value, &lookup))
+    }
+
+    fn uses_css_variables(&self) -> bool {
+        [
+            &self.background,
+            &self.text_color,
+            &self.grid_color,
+            &self.axis_color,
+            &self.border_color,
+            &self.tooltip_background,
+            &self.tooltip_text_color,
+            &self.pattern_color,
+            &self.selection_color,
+            &self.font,
+            &self.title_font,
+        ]
+        .into_iter()
+        .chain(&self.palette)
+        .any(|value| value.contains("var("))
+    }
+
+    fn map_values(&self, mut f: impl FnMut(&str) -> String) -> Theme {
+        Theme {
+            background: f(&self.background),
+            text_color: f(&self.text_color),
+            grid_color: f(&self.grid_color),
+            axis_color: f(&self.axis_color),
+            border_color: f(&self.border_color),
+            tooltip_background: f(&self.tooltip_background),
+            tooltip_text_color: f(&self.tooltip_text_color),
+            pattern_color: f(&self.pattern_color),
+            selection_color: f(&self.selection_color),
+            font: f(&self.font),
+            title_font: f(&self.title_font),
+            palette: self.palette.iter().map(|color| f(color)).collect(),
+        }
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::light()
+    }
+}
+
+fn default_palette() -> Vec<String> {
+    [
+        "#36a2eb", "#ff6384", "#ff9f40", "#ffcd56", "#4bc0c0", "#9966ff", "#c9cbcf",
+    ]
+    .iter()
+    .map(|color| color.to_string())
+    .collect()
+}
+
+/// Replaces every `var(--name[, fallback])` in `value` using `lookup`.
+fn resolve_var(value: &str, lookup: &impl Fn(&str) -> Option<String>) -> String {
+    let mut resolved = String::with_capacity(value.len());
+    let mut rest = value;
+
+    while let Some(start) = rest.find("var(") {
+        resolved.push_str(&rest[..start]);
+        let arguments = &rest[start + 4..];
+
+        // Find the closing parenthesis, skipping over nested ones in the fallback
+        let mut depth = 0;
+        let mut end = None;
+        for (i, c) in arguments.char_indices() {
+            match c {
+                '(' => depth += 1,
+                ')' if depth == 0 => {
+                    end = Some(i);
+                    break;
+                }
+                ')' => depth -= 1,
+                _ => {}
+            }
+        }
+
+        let Some(end) = end else {
+            // Unbalanced, leave the rest untouched
+            resolved.push_str(&rest[start..]);
+            return resolved;
+        };
+
+        let (name, fallback) = match arguments[..end].split_once(',') {
+            Some((name, fallback)) => (name.trim(), Some(fallback.trim())),
+            None => (arguments[..end].trim(), None),
+        };
+
+        match lookup(name) {
+            Some(value) => resolved.push_str(&value),
+            None => resolved.push_str(&resolve_var(fallback.unwrap_or_default(), lookup)),
+        }
+
+        rest = &arguments[end + 1..];
+    }
+
+    resolved.push_str(rest);
+    resolved
+}
+
+/// Provides a [`Theme`] to every chart rendered inside it.
+///
+///```ignore
+/// html! {
+///     <ThemeProvider context={Theme::dark()}>
+///         <BarChart data={data} />
+///     </ThemeProvider>
+/// }
+/// ```
+pub type ThemeProvider = ContextProvider<Theme>;
+
+/// The user's preferred color scheme, as reported by the `prefers-color-scheme` media query.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColorScheme {
+    Light,
+    Dark,
+}
+
+const DARK_SCHEME_QUERY: &str = "(prefers-color-scheme: dark)";
+
+/// Tracks `prefers-color-scheme`, re-rendering the component whenever it changes.
+#[hook]
+pub fn use_color_scheme() -> ColorScheme {
+    let prefers_dark = || {
+        window()
+            .and_then(|window| window.match_media(DARK_SCHEME_QUERY).ok().flatten())
+            .is_some_and(|query| query.matches())
+    };
+    let scheme = use_state(move || {
+        if prefers_dark() {
+            ColorScheme::Dark
+        } else {
+            ColorScheme::Light
+        }
+    });
+
+    {
+        let scheme = scheme.clone();
+        use_effect_with((), move |_| {
+            let listener = window()
+                .and_then(|window| window.match_media(DARK_SCHEME_QUERY).ok().flatten())
+                .map(|query| {
+                    EventListener::new(&query, "change", move |_event| {
+                        scheme.set(if prefers_dark() {
+                            ColorScheme::Dark
+                        } else {
+                            ColorScheme::Light
+                        });
+                    })
+                });
+
+            move || drop(listener)
+        });
+    }
+
+    *scheme
+}
+
+#[derive(Clone, Debug, PartialEq, Properties)]
+pub struct AutoThemeProviderProps {
+    /// Used while the user prefers a light color scheme.
+    #[prop_or_else(Theme::light)]
+    pub light: Theme,
+    /// Used while the user prefers a dark color scheme.
+    #[prop_or_else(Theme::dark)]
+    pub dark: Theme,
+    pub children: Html,
+}
+
+/// A [`ThemeProvider`] that follows `prefers-color-scheme`, switching between its `light` and
+/// `dark` themes and redrawing the charts inside it whenever the preference flips.
+///
+///```ignore
+/// html! {
+///     <AutoThemeProvider>
+///         <BarChart data={data} />
+///     </AutoThemeProvider>
+/// }
+/// ```
+#[function_component]
+pub fn AutoThemeProvider(props: &AutoThemeProviderProps) -> Html {
+    let theme = match use_color_scheme() {
+        ColorScheme::Light => props.light.clone(),
+        ColorScheme::Dark => props.dark.clone(),
+    };
+
+    html! {
+        <ThemeProvider context={theme}>
+            { props.children.clone() }
+        </ThemeProvider>
+    }
+}
+
+/// Resolves the theme a chart should draw with: the chart's own override if any, otherwise the
+/// nearest [`ThemeProvider`], otherwise [`Theme::light`].
+#[hook]
+pub fn use_theme(override_theme: Option<Theme>) -> Theme {
+    let provided = use_context::<Theme>();
+
+    override_theme.or(provided).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn palette_color_wraps_around() {
+        let theme = Theme::light();
+        let len = theme.palette.len();
+
+        assert_eq!(theme.palette_color(0), theme.palette_color(len));
+        assert_eq!(theme.palette_color(1), theme.palette[1]);
+    }
+
+    #[test]
+    fn explicit_colors_win_over_palette() {
+        let theme = Theme::dark();
+
+        assert_eq!(theme.color_or_palette("#123456", 3), "#123456");
+        assert_eq!(theme.color_or_palette("", 3), theme.palette[3]);
+    }
+
+    fn lookup(name: &str) -> Option<String> {
+        match name {
+            "--color-fg" => Some("#fafafa".to_string()),
+            "--font-family" => Some("Inter".to_string()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn resolves_defined_variables() {
+        assert_eq!(resolve_var("var(--color-fg)", &lookup), "#fafafa");
+        assert_eq!(
+            resolve_var("bold 12px var(--font-family)", &lookup),
+            "bold 12px Inter"
+        );
+    }
+
+    #[test]
+    fn falls_back_for_undefined_variables() {
+        assert_eq!(resolve_var("var(--missing, red)", &lookup), "red");
+        assert_eq!(
+            resolve_var("var(--missing, rgb(1, 2, 3))", &lookup),
+            "rgb(1, 2, 3)"
+        );
+        assert_eq!(
+            resolve_var("var(--missing, var(--color-fg))", &lookup),
+            "#fafafa"
+        );
+        assert_eq!(resolve_var("var(--missing)", &lookup), "");
+    }
+
+    #[test]
+    fn leaves_plain_values_untouched() {
+        assert_eq!(resolve_var("#cccccc", &lookup), "#cccccc");
+        assert_eq!(resolve_var("var(--color-fg", &lookup), "var(--color-fg");
+        assert!(!Theme::light().uses_css_variables());
+    }
+}
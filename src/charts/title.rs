@@ -0,0 +1,282 @@
+use std::rc::Rc;
+
+use crate::charts::scene::{Bounds, Label, Scene, SceneItem};
+use crate::charts::text::font_size;
+use crate::charts::theme::Theme;
+use crate::charts::zoom::PlotArea;
+
+/// A title and subtitle above a chart and a caption below it, e.g. for the source of the data.
+/// The chart is laid out in the space left between them.
+///
+///```
+/// # use visualize_yew::title::{ChartText, ChartTitles, TextAlign};
+/// let titles = ChartTitles::new("Monthly revenue")
+///     .with_subtitle("In thousands of dollars")
+///     .with_caption("Source: finance team")
+///     .with_align(TextAlign::Left);
+///
+/// let highlighted = ChartTitles {
+///     title: ChartText::new("Outages").with_color("#dc2626"),
+///     ..titles
+/// };
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct ChartTitles {
+    pub title: ChartText,
+    pub subtitle: ChartText,
+    /// Drawn below the chart.
+    pub caption: ChartText,
+}
+
+/// A line of text drawn with a chart. Leave `font` or `color` empty to use the active
+/// [`Theme`]'s, which is its title font for titles and its tick label font otherwise.
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct ChartText {
+    pub text: String,
+    pub align: TextAlign,
+    /// A CSS font shorthand, e.g. `"bold 16px sans-serif"`.
+    pub font: String,
+    pub color: String,
+}
+
+/// Where a line of text sits across the width of the chart.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum TextAlign {
+    Left,
+    #[default]
+    Center,
+    Right,
+}
+
+/// Space around the titles and the caption, in pixels.
+const MARGIN: f64 = 8.0;
+
+/// The height of a line of text, relative to its font size.
+const LINE_HEIGHT: f64 = 1.25;
+
+impl ChartText {
+    pub fn new(text: &str) -> Self {
+        Self {
+            text: text.into(),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_align(mut self, align: TextAlign) -> Self {
+        self.align = align;
+        self
+    }
+
+    pub fn with_font(mut self, font: &str) -> Self {
+        self.font = font.into();
+        self
+    }
+
+    pub fn with_color(mut self, color: &str) -> Self {
+        self.color = color.into();
+        self
+    }
+
+    fn font<'a>(&'a self, default: &'a str) -> &'a str {
+        match self.font.is_empty() {
+            true => default,
+            false => &self.font,
+        }
+    }
+
+    fn line_height(&self, default_font: &str) -> f64 {
+        match self.text.is_empty() {
+            true => 0.0,
+            false => font_size(self.font(default_font)) * LINE_HEIGHT,
+        }
+    }
+}
+
+impl ChartTitles {
+    pub fn new(title: &str) -> Self {
+        Self {
+            title: ChartText::new(title),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_subtitle(mut self, subtitle: &str) -> Self {
+        self.subtitle.text = subtitle.into();
+        self
+    }
+
+    pub fn with_caption(mut self, caption: &str) -> Self {
+        self.caption.text = caption.into();
+        self
+    }
+
+    /// Aligns the title, subtitle and caption alike.
+    pub fn with_align(mut self, align: TextAlign) -> Self {
+        self.title.align = align;
+        self.subtitle.align = align;
+        self.caption.align = align;
+        self
+    }
+
+    /// The height taken up above and below the chart.
+    pub(crate) fn space(&self, theme: &Theme) -> (f64, f64) {
+        let with_margins = |height: f64| match height > 0.0 {
+            true => height + MARGIN * 2.0,
+            false => 0.0,
+        };
+        (
+            with_margins(
+                self.title.line_height(&theme.title_font) + self.subtitle.line_height(&theme.font),
+            ),
+            with_margins(self.caption.line_height(&theme.font)),
+        )
+    }
+
+    /// The height left for the chart itself when it is `height` tall in all.
+    pub(crate) fn inner_height(&self, height: f64, theme: &Theme) -> f64 {
+        let (top, bottom) = self.space(theme);
+        (height - top - bottom).max(0.0)
+    }
+
+    /// Wraps `area`, which lays the plot out in the space left for the chart, so that it lays
+    /// it out in the whole chart instead.
+    pub(crate) fn inset(&self, area: PlotArea, theme: &Theme) -> PlotArea {
+        let titles = self.clone();
+        let theme = theme.clone();
+        Rc::new(move |width, height| {
+            let (top, _) = titles.space(&theme);
+            let (plot, full) = area(width, titles.inner_height(height, &theme));
+            (
+                Bounds {
+                    y: plot.y + top,
+                    ..plot
+                },
+                full,
+            )
+        })
+    }
+
+    /// Moves `chart`, laid out in the space left for it, below the titles and adds them and
+    /// the caption.
+    pub(crate) fn frame(&self, mut chart: Scene, theme: &Theme) -> Scene {
+        let (top, bottom) = self.space(theme);
+        if top == 0.0 && bottom == 0.0 {
+            return chart;
+        }
+
+        for item in &mut chart.items {
+            item.translate(0.0, top);
+        }
+        let height = chart.height + top + bottom;
+        let width = chart.width;
+        let mut line = |text: &ChartText, default_font: &str, y: f64| {
+            if text.text.is_empty() {
+                return;
+            }
+            let (x, align) = match text.align {
+                TextAlign::Left => (MARGIN, "left"),
+                TextAlign::Center => (width / 2.0, "center"),
+                TextAlign::Right => (width - MARGIN, "right"),
+            };
+            chart.items.push(SceneItem::Label(Label {
+                text: text.text.clone(),
+                position: (x, y),
+                font: text.font(default_font).to_string(),
+                color: match text.color.is_empty() {
+                    true => theme.text_color.clone(),
+                    false => text.color.clone(),
+                },
+                align: align.to_string(),
+                baseline: "top".to_string(),
+                rotation: 0.0,
+            }));
+        };
+
+        line(&self.title, &theme.title_font, MARGIN);
+        line(
+            &self.subtitle,
+            &theme.font,
+            MARGIN + self.title.line_height(&theme.title_font),
+        );
+        line(&self.caption, &theme.font, height - bottom + MARGIN);
+
+        Scene { height, ..chart }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::charts::scene::Circle;
+    use crate::charts::zoom::ViewRange;
+
+    #[test]
+    fn frames_the_chart_between_the_titles_and_the_caption() {
+        let theme = Theme::light();
+        let titles = ChartTitles::new("Revenue")
+            .with_subtitle("2024")
+            .with_caption("Source: finance");
+        let (top, bottom) = titles.space(&theme);
+        // A 12px title, a 10px subtitle and a 10px caption
+        assert_eq!((top, bottom), (43.5, 28.5));
+        assert_eq!(titles.inner_height(200.0, &theme), 128.0);
+
+        let chart = Scene {
+            width: 300.0,
+            height: 128.0,
+            background: theme.background.clone(),
+            items: vec![SceneItem::Circle(Circle {
+                center: (10.0, 10.0),
+                radius: 2.0,
+                fill: None,
+                outline: None,
+                point: None,
+            })],
+        };
+        let scene = titles.frame(chart, &theme);
+        assert_eq!(scene.height, 200.0);
+        assert!(
+            matches!(&scene.items[0], SceneItem::Circle(circle) if circle.center == (10.0, 53.5))
+        );
+
+        let labels = scene.items[1..]
+            .iter()
+            .map(|item| match item {
+                SceneItem::Label(label) => (label.text.as_str(), label.position),
+                _ => panic!("expected a label"),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            labels,
+            vec![
+                ("Revenue", (150.0, 8.0)),
+                ("2024", (150.0, 23.0)),
+                ("Source: finance", (150.0, 179.5)),
+            ]
+        );
+    }
+
+    #[test]
+    fn charts_without_titles_are_left_alone() {
+        let theme = Theme::light();
+        let titles = ChartTitles::default();
+        assert_eq!(titles.space(&theme), (0.0, 0.0));
+
+        let area: PlotArea = Rc::new(|width, height| {
+            (
+                Bounds {
+                    x: 0.0,
+                    y: 10.0,
+                    width,
+                    height,
+                },
+                ViewRange::new((0.0, 1.0), (0.0, 1.0)),
+            )
+        });
+        let (plot, _) = titles.inset(area.clone(), &theme)(100.0, 100.0);
+        assert_eq!(plot, area(100.0, 100.0).0);
+
+        let (plot, _) = ChartTitles::new("Revenue").inset(area, &theme)(100.0, 100.0);
+        assert_eq!((plot.y, plot.height), (41.0, 69.0));
+    }
+}
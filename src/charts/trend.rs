@@ -0,0 +1,457 @@
+use crate::charts::annotation::Dash;
+use crate::charts::scene::{Band, Path, SceneItem, Stroke};
+
+/// A trend line drawn over a series of a line chart, optionally with a band of standard
+/// deviations around it.
+///
+///```
+/// # use visualize_yew::charts::line_chart::line_chart::Series;
+/// # use visualize_yew::trend::Trend;
+/// let series = Series::new("Visitors", "#36a2eb")
+///     .with_trend(Trend::linear())
+///     .with_trend(Trend::moving_average(7).with_deviations(2));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Trend {
+    pub kind: TrendKind,
+    /// Shades the band this many standard deviations above and below the trend. Zero leaves
+    /// the band out.
+    pub deviations: u32,
+    /// Defaults to the series' color.
+    pub color: String,
+    pub dash: Dash,
+}
+
+/// How a [`Trend`] follows the data. The x value of a point is its index.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TrendKind {
+    /// The least squares straight line.
+    Linear,
+    /// The least squares polynomial of the given degree.
+    Polynomial(u32),
+    /// The curve `a·e^(b·x)` that fits the logarithm of the positive values best.
+    Exponential,
+    /// The mean of every point and the ones before it, over a window of this many points.
+    MovingAverage(usize),
+    /// The exponentially weighted mean, with the weight falling off as over a window of this
+    /// many points.
+    ExponentialMovingAverage(usize),
+}
+
+impl Trend {
+    pub fn new(kind: TrendKind) -> Self {
+        Self {
+            kind,
+            deviations: 0,
+            color: String::new(),
+            dash: Dash::Dashed,
+        }
+    }
+
+    pub fn linear() -> Self {
+        Self::new(TrendKind::Linear)
+    }
+
+    pub fn polynomial(degree: u32) -> Self {
+        Self::new(TrendKind::Polynomial(degree))
+    }
+
+    pub fn exponential() -> Self {
+        Self::new(TrendKind::Exponential)
+    }
+
+    pub fn moving_average(window: usize) -> Self {
+        Self::new(TrendKind::MovingAverage(window))
+    }
+
+    pub fn exponential_moving_average(window: usize) -> Self {
+        Self::new(TrendKind::ExponentialMovingAverage(window))
+    }
+
+    pub fn with_deviations(mut self, deviations: u32) -> Self {
+        self.deviations = deviations;
+        self
+    }
+
+    pub fn with_color(mut self, color: &str) -> Self {
+        self.color = color.into();
+        self
+    }
+
+    pub fn with_dash(mut self, dash: Dash) -> Self {
+        self.dash = dash;
+        self
+    }
+
+    /// Fits the trend to `values`, e.g. to show its equation and R² next to the chart. `None`
+    /// when there are too few values to fit it to.
+    ///
+    ///```
+    /// # use visualize_yew::trend::Trend;
+    /// let fit = Trend::linear().fit(&[1.0, 3.0, 5.0, 7.0]).unwrap();
+    ///
+    /// assert_eq!(fit.coefficients, vec![1.0, 2.0]);
+    /// assert_eq!(fit.r_squared, 1.0);
+    /// ```
+    pub fn fit(&self, values: &[f64]) -> Option<TrendFit> {
+        let (coefficients, trend) = match self.kind {
+            TrendKind::Linear => polynomial_trend(values, 1)?,
+            TrendKind::Polynomial(degree) => polynomial_trend(values, degree as usize)?,
+            TrendKind::Exponential => exponential_trend(values)?,
+            TrendKind::MovingAverage(window) => {
+                (Vec::new(), moving_average(values, window.max(1))?)
+            }
+            TrendKind::ExponentialMovingAverage(window) => (
+                Vec::new(),
+                exponential_moving_average(values, window.max(1))?,
+            ),
+        };
+
+        let deviations = match self.kind {
+            // The spread of the data within the window, like Bollinger bands
+            TrendKind::MovingAverage(window) | TrendKind::ExponentialMovingAverage(window) => {
+                let window = window.max(1);
+                trend
+                    .iter()
+                    .enumerate()
+                    .map(|(i, mean)| {
+                        mean.map(|mean| {
+                            deviation(&values[(i + 1).saturating_sub(window)..=i], mean)
+                        })
+                    })
+                    .collect()
+            }
+            // The spread of the data around the curve, the same all along it
+            _ => {
+                let residuals = residuals(values, &trend);
+                let spread = (residuals / values.len() as f64).sqrt();
+                vec![Some(spread); values.len()]
+            }
+        };
+
+        Some(TrendFit {
+            coefficients,
+            r_squared: r_squared(values, &trend),
+            values: trend,
+            deviations,
+        })
+    }
+}
+
+/// A [`Trend`] fitted to the values of a series.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TrendFit {
+    /// `[c0, c1, c2, …]` of the polynomial `c0 + c1·x + c2·x² + …` for linear and polynomial
+    /// trends, and `[a, b]` of `a·e^(b·x)` for exponential ones. Empty for moving averages.
+    pub coefficients: Vec<f64>,
+    /// How much of the variance of the values the trend explains, from 0 to 1.
+    pub r_squared: f64,
+    /// The trend at every value, or `None` before a moving average has a full window.
+    pub values: Vec<Option<f64>>,
+    /// The standard deviation of the values around the trend at every value.
+    pub deviations: Vec<Option<f64>>,
+}
+
+/// The least squares polynomial of at most `degree`, and its value at every index.
+fn polynomial_trend(values: &[f64], degree: usize) -> Option<(Vec<f64>, Vec<Option<f64>>)> {
+    let points = values
+        .iter()
+        .enumerate()
+        .map(|(i, &value)| (i as f64, value))
+        .collect::<Vec<_>>();
+    let coefficients = least_squares(&points, degree)?;
+    let trend = (0..values.len())
+        .map(|i| Some(evaluate(&coefficients, i as f64)))
+        .collect();
+
+    Some((coefficients, trend))
+}
+
+/// The curve `a·e^(b·x)` through the positive values, and its value at every index.
+fn exponential_trend(values: &[f64]) -> Option<(Vec<f64>, Vec<Option<f64>>)> {
+    let points = values
+        .iter()
+        .enumerate()
+        .filter(|(_, &value)| value > 0.0)
+        .map(|(i, &value)| (i as f64, value.ln()))
+        .collect::<Vec<_>>();
+    if points.len() < 2 {
+        return None;
+    }
+
+    let line = least_squares(&points, 1)?;
+    let (a, b) = (line[0].exp(), line[1]);
+    let trend = (0..values.len())
+        .map(|i| Some(a * (b * i as f64).exp()))
+        .collect();
+
+    Some((vec![a, b], trend))
+}
+
+fn moving_average(values: &[f64], window: usize) -> Option<Vec<Option<f64>>> {
+    if values.is_empty() {
+        return None;
+    }
+
+    let mut sum = 0.0;
+    let trend = (0..values.len())
+        .map(|i| {
+            sum += values[i];
+            if i >= window {
+                sum -= values[i - window];
+            }
+            (i + 1 >= window).then(|| sum / window as f64)
+        })
+        .collect();
+
+    Some(trend)
+}
+
+fn exponential_moving_average(values: &[f64], window: usize) -> Option<Vec<Option<f64>>> {
+    let alpha = 2.0 / (window as f64 + 1.0);
+    let mut mean = *values.first()?;
+    let trend = values
+        .iter()
+        .map(|&value| {
+            mean = alpha * value + (1.0 - alpha) * mean;
+            Some(mean)
+        })
+        .collect();
+
+    Some(trend)
+}
+
+/// The coefficients of the polynomial of at most `degree` closest to `points`, lowest power
+/// first. The degree is lowered to fit through fewer points than it needs.
+fn least_squares(points: &[(f64, f64)], degree: usize) -> Option<Vec<f64>> {
+    let size = degree.min(points.len().checked_sub(1)?) + 1;
+
+    // The normal equations, as rows of an augmented matrix
+    let mut rows = (0..size)
+        .map(|row| {
+            let mut equation = (0..size)
+                .map(|column| {
+                    points
+                        .iter()
+                        .map(|(x, _)| x.powi((row + column) as i32))
+                        .sum()
+                })
+                .collect::<Vec<f64>>();
+            equation.push(points.iter().map(|(x, y)| x.powi(row as i32) * y).sum());
+            equation
+        })
+        .collect::<Vec<_>>();
+
+    // Gaussian elimination with partial pivoting
+    for column in 0..size {
+        let pivot = (column..size)
+            .max_by(|&a, &b| rows[a][column].abs().total_cmp(&rows[b][column].abs()))?;
+        if rows[pivot][column].abs() < f64::EPSILON {
+            return None;
+        }
+        rows.swap(column, pivot);
+
+        let (done, rest) = rows.split_at_mut(column + 1);
+        let pivot_row = &done[column];
+        for row in rest {
+            let factor = row[column] / pivot_row[column];
+            for (value, pivot_value) in row.iter_mut().zip(pivot_row).skip(column) {
+                *value -= factor * pivot_value;
+            }
+        }
+    }
+
+    let mut coefficients = vec![0.0; size];
+    for row in (0..size).rev() {
+        let known = (row + 1..size)
+            .map(|k| rows[row][k] * coefficients[k])
+            .sum::<f64>();
+        coefficients[row] = (rows[row][size] - known) / rows[row][row];
+    }
+
+    Some(coefficients)
+}
+
+fn evaluate(coefficients: &[f64], x: f64) -> f64 {
+    coefficients
+        .iter()
+        .rev()
+        .fold(0.0, |value, coefficient| value * x + coefficient)
+}
+
+/// The sum of the squared differences between the values and the trend, where it is defined.
+fn residuals(values: &[f64], trend: &[Option<f64>]) -> f64 {
+    values
+        .iter()
+        .zip(trend)
+        .filter_map(|(value, trend)| trend.map(|trend| (value - trend).powi(2)))
+        .sum()
+}
+
+fn r_squared(values: &[f64], trend: &[Option<f64>]) -> f64 {
+    let fitted = values
+        .iter()
+        .zip(trend)
+        .filter(|(_, trend)| trend.is_some())
+        .map(|(&value, _)| value)
+        .collect::<Vec<_>>();
+    let mean = fitted.iter().sum::<f64>() / fitted.len() as f64;
+    let total = fitted
+        .iter()
+        .map(|value| (value - mean).powi(2))
+        .sum::<f64>();
+    let residuals = residuals(values, trend);
+
+    // Flat data is explained exactly by any trend that follows it
+    if total == 0.0 {
+        return f64::from(residuals < 1e-9);
+    }
+    (1.0 - residuals / total).max(0.0)
+}
+
+fn deviation(values: &[f64], mean: f64) -> f64 {
+    (values
+        .iter()
+        .map(|value| (value - mean).powi(2))
+        .sum::<f64>()
+        / values.len() as f64)
+        .sqrt()
+}
+
+/// How opaque the band of standard deviations is, so that the lines show through it.
+const BAND_OPACITY: f64 = 0.13;
+
+/// The band and line of `trend` over the points at `indices`, in `color` unless the trend has
+/// its own. `position` is where a value is drawn at an index.
+pub(crate) fn trend_items(
+    trend: &Trend,
+    fit: &TrendFit,
+    indices: impl Iterator<Item = usize>,
+    position: impl Fn(usize, f64) -> (f64, f64),
+    color: &str,
+    width: f64,
+) -> Vec<SceneItem> {
+    let color = match trend.color.is_empty() {
+        true => color,
+        false => trend.color.as_str(),
+    };
+    let spread = trend.deviations as f64;
+    let (mut points, mut upper, mut lower) = (Vec::new(), Vec::new(), Vec::new());
+    for i in indices {
+        let Some(value) = fit.values.get(i).copied().flatten() else {
+            continue;
+        };
+        points.push(position(i, value));
+        if let Some(deviation) = fit.deviations.get(i).copied().flatten() {
+            upper.push(position(i, value + spread * deviation));
+            lower.push(position(i, value - spread * deviation));
+        }
+    }
+
+    let mut items = Vec::new();
+    if trend.deviations > 0 && upper.len() > 1 {
+        items.push(SceneItem::Band(Band {
+            upper,
+            lower,
+            fill: color.to_string(),
+            opacity: BAND_OPACITY,
+        }));
+    }
+    if points.len() > 1 {
+        items.push(SceneItem::Path(Path {
+            points,
+            stroke: Stroke {
+                color: color.to_string(),
+                width,
+            },
+            dash: trend.dash.segments(),
+        }));
+    }
+
+    items
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: &[f64], expected: &[f64]) {
+        assert_eq!(actual.len(), expected.len(), "{:?}", actual);
+        for (actual, expected) in actual.iter().zip(expected) {
+            assert!((actual - expected).abs() < 1e-6, "{:?}", actual);
+        }
+    }
+
+    #[test]
+    fn regressions_recover_the_curve_behind_the_data() {
+        let squares = (0..6)
+            .map(|x| 2.0 + 0.5 * (x * x) as f64)
+            .collect::<Vec<_>>();
+        let fit = Trend::polynomial(2).fit(&squares).unwrap();
+        assert_close(&fit.coefficients, &[2.0, 0.0, 0.5]);
+        assert!((fit.r_squared - 1.0).abs() < 1e-9);
+
+        let growth = (0..5)
+            .map(|x| 3.0 * (0.5 * x as f64).exp())
+            .collect::<Vec<_>>();
+        let fit = Trend::exponential().fit(&growth).unwrap();
+        assert_close(&fit.coefficients, &[3.0, 0.5]);
+
+        // Too high a degree for the points is lowered, and noise lowers R²
+        let fit = Trend::polynomial(5).fit(&[1.0, 4.0]).unwrap();
+        assert_close(&fit.coefficients, &[1.0, 3.0]);
+        let fit = Trend::linear().fit(&[0.0, 2.0, 0.0, 2.0]).unwrap();
+        assert!(fit.r_squared < 0.5);
+        assert_close(
+            &fit.deviations.iter().flatten().copied().collect::<Vec<_>>(),
+            &[0.8944272; 4],
+        );
+
+        assert_eq!(Trend::linear().fit(&[]), None);
+        assert_eq!(Trend::exponential().fit(&[-1.0, 0.0, 5.0]), None);
+    }
+
+    #[test]
+    fn moving_averages_follow_a_window_of_points() {
+        let values = [1.0, 3.0, 5.0, 3.0, 1.0];
+
+        let fit = Trend::moving_average(3).fit(&values).unwrap();
+        assert_eq!(
+            fit.values,
+            vec![None, None, Some(3.0), Some(11.0 / 3.0), Some(3.0)]
+        );
+        assert!(fit.coefficients.is_empty());
+        assert_eq!(fit.deviations[1], None);
+        assert!((fit.deviations[2].unwrap() - (8.0f64 / 3.0).sqrt()).abs() < 1e-9);
+
+        let fit = Trend::exponential_moving_average(3).fit(&values).unwrap();
+        assert_close(
+            &fit.values.iter().flatten().copied().collect::<Vec<_>>(),
+            &[1.0, 2.0, 3.5, 3.25, 2.125],
+        );
+    }
+
+    #[test]
+    fn bands_are_drawn_under_the_line_where_the_trend_is_defined() {
+        let trend = Trend::moving_average(2).with_deviations(1);
+        let fit = trend.fit(&[0.0, 2.0, 4.0]).unwrap();
+        let items = trend_items(
+            &trend,
+            &fit,
+            0..3,
+            |i, value| (i as f64, value),
+            "#36a2eb",
+            2.0,
+        );
+
+        let [SceneItem::Band(band), SceneItem::Path(path)] = items.as_slice() else {
+            panic!("expected a band and a line, got {:?}", items);
+        };
+        assert_eq!(band.upper, vec![(1.0, 2.0), (2.0, 4.0)]);
+        assert_eq!(band.lower, vec![(1.0, 0.0), (2.0, 2.0)]);
+        assert_eq!(band.fill, "#36a2eb");
+        assert_eq!(band.opacity, BAND_OPACITY);
+        assert_eq!(path.points, vec![(1.0, 1.0), (2.0, 3.0)]);
+        assert_eq!(path.dash, vec![6.0, 4.0]);
+    }
+}
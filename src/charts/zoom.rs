@@ -0,0 +1,390 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use web_sys::Element;
+use yew::prelude::*;
+
+use crate::charts::interaction::CanvasState;
+use crate::charts::scene::Bounds;
+use crate::charts::theme::Theme;
+
+/// Which axes zooming and panning change.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum ZoomAxes {
+    /// Only the x-axis, so the y-axis keeps showing the full range of values.
+    X,
+    Y,
+    #[default]
+    Both,
+}
+
+impl ZoomAxes {
+    fn x(&self) -> bool {
+        matches!(self, ZoomAxes::X | ZoomAxes::Both)
+    }
+
+    fn y(&self) -> bool {
+        matches!(self, ZoomAxes::Y | ZoomAxes::Both)
+    }
+}
+
+/// Zoom and pan options shared by the cartesian charts.
+///
+/// When enabled, the mouse wheel and pinching zoom around the pointer, and dragging pans.
+///
+///```
+/// # use visualize_yew::zoom::{ZoomAxes, ZoomConfig};
+/// let zoom = ZoomConfig {
+///     max_zoom: 20,
+///     ..ZoomConfig::new(ZoomAxes::X)
+/// };
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ZoomConfig {
+    pub enabled: bool,
+    pub axes: ZoomAxes,
+    /// How far the chart can be zoomed in, as a multiple of the full range.
+    pub max_zoom: u32,
+    /// Shows a "Reset zoom" button over the chart while it is zoomed.
+    pub show_reset_button: bool,
+}
+
+impl ZoomConfig {
+    pub fn new(axes: ZoomAxes) -> Self {
+        Self {
+            enabled: true,
+            axes,
+            ..Default::default()
+        }
+    }
+
+    /// Keeps the browser from scrolling or zooming the page while the chart is dragged or
+    /// pinched.
+    pub(crate) fn canvas_style(&self) -> &'static str {
+        match self.enabled {
+            true => "touch-action: none;",
+            false => "",
+        }
+    }
+}
+
+impl Default for ZoomConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            axes: ZoomAxes::default(),
+            max_zoom: 100,
+            show_reset_button: true,
+        }
+    }
+}
+
+/// The part of a chart's data that is visible.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ViewRange {
+    /// Along the x-axis, in data point indices. Fractional when a point is partly in view.
+    pub x: (f64, f64),
+    /// Along the y-axis, in data values.
+    pub y: (f64, f64),
+}
+
+impl ViewRange {
+    pub fn new(x: (f64, f64), y: (f64, f64)) -> Self {
+        Self { x, y }
+    }
+
+    /// Zooms in by `factor` on `axes`, or out when `factor` is below `1.0`, keeping `anchor`
+    /// in place. The result stays within `full` and at most `max_zoom` times smaller.
+    pub fn zoom(
+        &self,
+        anchor: (f64, f64),
+        factor: f64,
+        axes: ZoomAxes,
+        full: &ViewRange,
+        max_zoom: f64,
+    ) -> Self {
+        let zoom_axis = |range: (f64, f64), anchor: f64, full: (f64, f64)| {
+            let full_span = full.1 - full.0;
+            let span = ((range.1 - range.0) / factor).clamp(full_span / max_zoom, full_span);
+            let start = anchor - (anchor - range.0) * span / (range.1 - range.0);
+            clamp_axis((start, start + span), full)
+        };
+
+        Self {
+            x: match axes.x() {
+                true => zoom_axis(self.x, anchor.0, full.x),
+                false => self.x,
+            },
+            y: match axes.y() {
+                true => zoom_axis(self.y, anchor.1, full.y),
+                false => self.y,
+            },
+        }
+    }
+
+    /// Moves the range by `delta` on `axes`, without leaving `full`.
+    pub fn pan(&self, delta: (f64, f64), axes: ZoomAxes, full: &ViewRange) -> Self {
+        Self {
+            x: match axes.x() {
+                true => clamp_axis((self.x.0 + delta.0, self.x.1 + delta.0), full.x),
+                false => self.x,
+            },
+            y: match axes.y() {
+                true => clamp_axis((self.y.0 + delta.1, self.y.1 + delta.1), full.y),
+                false => self.y,
+            },
+        }
+    }
+
+    /// The data coordinates shown at `(x, y)` when this range fills `plot`.
+    pub fn to_data(&self, plot: &Bounds, (x, y): (f64, f64)) -> (f64, f64) {
+        (
+            self.x.0 + (x - plot.x) / plot.width * (self.x.1 - self.x.0),
+            self.y.0 + (plot.y + plot.height - y) / plot.height * (self.y.1 - self.y.0),
+        )
+    }
+}
+
+/// Shifts `range` into `full`, or shrinks it to `full` if it is larger.
+fn clamp_axis(range: (f64, f64), full: (f64, f64)) -> (f64, f64) {
+    let span = (range.1 - range.0).min(full.1 - full.0);
+    let start = range.0.clamp(full.0, full.1 - span);
+    (start, start + span)
+}
+
+/// Where a chart drawn at a size puts its plot area, and the range it shows when not zoomed.
+pub(crate) type PlotArea = Rc<dyn Fn(f64, f64) -> (Bounds, ViewRange)>;
+
+/// The gesture handlers of a chart, to be set on its canvas.
+pub(crate) struct PointerHandlers {
+    pub onwheel: Callback<WheelEvent>,
+    pub onpointerdown: Callback<PointerEvent>,
+    pub onpointermove: Callback<PointerEvent>,
+    pub onpointerup: Callback<PointerEvent>,
+    pub onpointerleave: Callback<PointerEvent>,
+    pub reset: Callback<MouseEvent>,
+}
+
+/// Builds the handlers that change the range stored in `state` and redraw the chart.
+///
+/// `zoomed` tracks whether the chart is zoomed at all, for showing the reset button, and
+/// `on_zoom` is called with the visible range after every change.
+pub(crate) fn zoom_handlers(
+    config: ZoomConfig,
+    state: &CanvasState,
+    area: PlotArea,
+    zoomed: &UseStateHandle<bool>,
+    on_zoom: Option<Callback<ViewRange>>,
+) -> PointerHandlers {
+    // The last position of every pointer pressed on the canvas
+    let pointers = Rc::new(RefCell::new(Vec::<(i32, (f64, f64))>::new()));
+    let update = Rc::new(ViewUpdate {
+        state: state.clone(),
+        area,
+        zoomed: zoomed.clone(),
+        on_zoom,
+    });
+    let max_zoom = config.max_zoom.max(1) as f64;
+
+    let onwheel = {
+        let update = update.clone();
+        Callback::from(move |event: WheelEvent| {
+            if !config.enabled {
+                return;
+            }
+            event.prevent_default();
+
+            // Line and page deltas are roughly 16 and 800 pixels
+            let pixels = match event.delta_mode() {
+                WheelEvent::DOM_DELTA_LINE => event.delta_y() * 16.0,
+                WheelEvent::DOM_DELTA_PAGE => event.delta_y() * 800.0,
+                _ => event.delta_y(),
+            };
+            let factor = (-pixels * 0.002).exp();
+            let pointer = (event.offset_x() as f64, event.offset_y() as f64);
+            update.apply(|view, plot, full| {
+                let anchor = view.to_data(plot, pointer);
+                Some(view.zoom(anchor, factor, config.axes, full, max_zoom))
+            });
+        })
+    };
+
+    let onpointerdown = {
+        let pointers = pointers.clone();
+        Callback::from(move |event: PointerEvent| {
+            if !config.enabled {
+                return;
+            }
+            if let Some(canvas) = event.target_dyn_into::<Element>() {
+                let _ignored_result = canvas.set_pointer_capture(event.pointer_id());
+            }
+            pointers.borrow_mut().push((
+                event.pointer_id(),
+                (event.offset_x() as f64, event.offset_y() as f64),
+            ));
+        })
+    };
+
+    let onpointermove = {
+        let pointers = pointers.clone();
+        let update = update.clone();
+        Callback::from(move |event: PointerEvent| {
+            let position = (event.offset_x() as f64, event.offset_y() as f64);
+            let (before, after): (Vec<_>, Vec<_>) = {
+                let mut pointers = pointers.borrow_mut();
+                let before = pointers.iter().map(|(_, position)| *position).collect();
+                let Some(pointer) = pointers
+                    .iter_mut()
+                    .find(|(id, _)| *id == event.pointer_id())
+                else {
+                    return;
+                };
+                pointer.1 = position;
+                let after = pointers.iter().map(|(_, position)| *position).collect();
+                (before, after)
+            };
+
+            update.apply(|view, plot, full| match (&before[..], &after[..]) {
+                // Drag the data along with one pointer
+                ([from], [to]) => {
+                    let delta = (
+                        -(to.0 - from.0) / plot.width * (view.x.1 - view.x.0),
+                        (to.1 - from.1) / plot.height * (view.y.1 - view.y.0),
+                    );
+                    Some(view.pan(delta, config.axes, full))
+                }
+                // Pinch with two, around the point between them
+                ([a, b], [c, d]) => {
+                    let distance = |a: &(f64, f64), b: &(f64, f64)| (a.0 - b.0).hypot(a.1 - b.1);
+                    let middle = ((c.0 + d.0) / 2.0, (c.1 + d.1) / 2.0);
+                    let factor = distance(c, d) / distance(a, b).max(1.0);
+                    let anchor = view.to_data(plot, middle);
+                    Some(view.zoom(anchor, factor, config.axes, full, max_zoom))
+                }
+                _ => Some(*view),
+            });
+        })
+    };
+
+    let onpointerup = {
+        let pointers = pointers.clone();
+        Callback::from(move |event: PointerEvent| {
+            pointers
+                .borrow_mut()
+                .retain(|(id, _)| *id != event.pointer_id());
+        })
+    };
+
+    let reset = Callback::from(move |_event: MouseEvent| update.apply(|_, _, _| None));
+
+    PointerHandlers {
+        onwheel,
+        onpointerdown,
+        onpointermove,
+        onpointerup,
+        onpointerleave: Callback::noop(),
+        reset,
+    }
+}
+
+/// Applies changes to the visible range of a chart.
+struct ViewUpdate {
+    state: CanvasState,
+    area: PlotArea,
+    zoomed: UseStateHandle<bool>,
+    on_zoom: Option<Callback<ViewRange>>,
+}
+
+impl ViewUpdate {
+    /// Replaces the visible range with what `change` makes of it, given the range, the plot
+    /// area and the full range. Ranges that show everything count as not zoomed.
+    fn apply(&self, change: impl FnOnce(&ViewRange, &Bounds, &ViewRange) -> Option<ViewRange>) {
+        let (width, height) = self.state.size();
+        let (plot, full) = (self.area)(width, height);
+        if full.x.1 <= full.x.0 || full.y.1 <= full.y.0 {
+            // Nothing to zoom into
+            return;
+        }
+        let current = self.state.view().unwrap_or(full);
+        let view = change(&current, &plot, &full).filter(|view| *view != full);
+
+        self.state.set_view(view);
+        self.state.redraw();
+        self.zoomed.set(view.is_some());
+        if let Some(on_zoom) = &self.on_zoom {
+            on_zoom.emit(view.unwrap_or(full));
+        }
+    }
+}
+
+/// The "Reset zoom" button shown over a zoomed chart.
+pub(crate) fn reset_button(theme: &Theme, onclick: Callback<MouseEvent>) -> Html {
+    let style = format!(
+        "position: absolute; top: 4px; right: 4px; padding: 2px 8px; border-radius: 4px; \
+         cursor: pointer; font: {}; background: {}; color: {}; border: 1px solid {};",
+        theme.font, theme.tooltip_background, theme.tooltip_text_color, theme.grid_color
+    );
+
+    html! {
+        <button type="button" style={style} {onclick}>{ "Reset zoom" }</button>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FULL: ViewRange = ViewRange {
+        x: (0.0, 100.0),
+        y: (0.0, 50.0),
+    };
+
+    #[test]
+    fn zooms_around_the_anchor() {
+        let view = FULL.zoom((25.0, 10.0), 2.0, ZoomAxes::Both, &FULL, 100.0);
+
+        assert_eq!(view, ViewRange::new((12.5, 62.5), (5.0, 30.0)));
+        // Zooming out again ends up where it started
+        assert_eq!(
+            view.zoom((25.0, 10.0), 0.5, ZoomAxes::Both, &FULL, 100.0),
+            FULL
+        );
+    }
+
+    #[test]
+    fn locked_axes_and_limits_are_respected() {
+        let view = FULL.zoom((50.0, 25.0), 1000.0, ZoomAxes::X, &FULL, 10.0);
+
+        assert_eq!(view, ViewRange::new((45.0, 55.0), (0.0, 50.0)));
+        assert_eq!(
+            view.zoom((50.0, 25.0), 0.001, ZoomAxes::X, &FULL, 10.0),
+            FULL
+        );
+    }
+
+    #[test]
+    fn panning_stays_within_the_data() {
+        let view = ViewRange::new((10.0, 30.0), (0.0, 50.0));
+
+        assert_eq!(
+            view.pan((-15.0, 5.0), ZoomAxes::Both, &FULL),
+            ViewRange::new((0.0, 20.0), (0.0, 50.0))
+        );
+        assert_eq!(
+            view.pan((5.0, 5.0), ZoomAxes::Y, &FULL),
+            ViewRange::new((10.0, 30.0), (0.0, 50.0))
+        );
+    }
+
+    #[test]
+    fn maps_pixels_to_data() {
+        let plot = Bounds {
+            x: 50.0,
+            y: 50.0,
+            width: 200.0,
+            height: 100.0,
+        };
+
+        assert_eq!(FULL.to_data(&plot, (150.0, 150.0)), (50.0, 0.0));
+        assert_eq!(FULL.to_data(&plot, (50.0, 50.0)), (0.0, 50.0));
+    }
+}
@@ -15,17 +15,22 @@
 //! visualize-yew = { version = "0.2x.x", features = ["BarChart", "PieChart"] }
 //! ```
 //!
+//! ## Theming
+//!
+//! Every chart draws with a [`theme::Theme`]. Wrap your charts in a [`theme::ThemeProvider`] to
+//! switch all of them at once, or pass a `theme` prop to override a single chart.
+//!
 //! ## Example
 //!
-//! ```rust
-//! use visualize_yew::pie_chart::{DataPoint as PieChartData, PieChart};
+//! ```rust,ignore
+//! use visualize_yew::pie_chart::{DataPoint as PieChartData, PieChart, PieChartConfig};
 //!
 //! #[function_component]
 //! fn Home() -> Html {
 //!     let mut pie_chart_config = PieChartConfig::default();
 //!     pie_chart_config.show_legend = true;
 //!
-//!     let pie_data = vec![
+//!     let pie_chart_data = vec![
 //!         PieChartData::new("A", 10, ""),
 //!         PieChartData::new("B", 20, ""),
 //!         PieChartData::new("C", 30, ""),
@@ -66,3 +71,69 @@ pub use charts::line_chart::line_chart;
 ///
 /// Enable this via the `DoughnutChart` feature in Cargo.toml.
 pub use charts::doughnut_chart::doughnut_chart;
+
+/// Animation options shared by all charts.
+pub use charts::animation;
+
+/// Reference lines, bands, markers and thresholds for the cartesian charts.
+pub use charts::annotation;
+
+/// Selecting a range of the cartesian charts by dragging across them.
+pub use charts::brush;
+
+/// The drawing interface charts are painted through.
+pub use charts::canvas;
+
+/// A crosshair and shared tooltip that follow the pointer across line charts.
+pub use charts::crosshair;
+
+/// Downsampling of long lines before they are drawn.
+pub use charts::decimation;
+
+/// PNG export shared by all charts.
+pub use charts::export;
+
+/// Hover, zoom and legend state shared between charts.
+pub use charts::group;
+
+/// Keyboard navigation and tooltips shared by all charts.
+pub use charts::interaction;
+
+/// Error bars and confidence intervals for the cartesian charts.
+pub use charts::interval;
+
+/// Legend placement, overflow, swatches and label formatting, and legends drawn in exports.
+pub use charts::legend;
+
+/// Pattern fills shared by all charts.
+pub use charts::pattern;
+
+#[cfg(feature = "Raster")]
+/// PNG and RGBA rendering on the CPU, without a browser.
+///
+/// Enable this via the `Raster` feature in Cargo.toml.
+pub use charts::raster;
+
+/// Chart layouts as shapes and labels, for hit testing and custom overlays.
+pub use charts::scene;
+
+/// Sizing options shared by all charts.
+pub use charts::sizing;
+
+/// Live data for line charts, pushed without re-rendering.
+pub use charts::streaming;
+
+/// SVG rendering that works without a browser.
+pub use charts::svg;
+
+/// Colors and fonts shared by all charts.
+pub use charts::theme;
+
+/// Titles, subtitles and captions shared by all charts.
+pub use charts::title;
+
+/// Trend lines and statistical overlays for line charts.
+pub use charts::trend;
+
+/// Zooming and panning for the cartesian charts.
+pub use charts::zoom;